@@ -0,0 +1,26 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use moonlander_trainer::generators::coding::create_tree_graph;
+use std::hint::black_box;
+
+/// Compares building `TypingPattern` trees with writing into a reused buffer, on the default
+/// drill tree and two of its parts.
+fn generate(c: &mut Criterion) {
+    let graph = create_tree_graph().unwrap();
+    for name in ["tree", "repeated_subtrees", "number"] {
+        let generator = graph.find(name).unwrap();
+        let mut group = c.benchmark_group(name);
+        group.bench_function("generate", |b| b.iter(|| black_box(generator.generate())));
+        let mut pattern = String::new();
+        group.bench_function("generate_into", |b| {
+            b.iter(|| {
+                pattern.clear();
+                generator.generate_into(&mut pattern);
+                black_box(&pattern);
+            })
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, generate);
+criterion_main!(benches);
@@ -0,0 +1,61 @@
+use moonlander_trainer::hid::hid::read_reports;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, Write};
+use std::time::Instant;
+
+/// Records raw reports, one per line as the milliseconds since the start followed by the bytes
+/// in hex, until `count` reports were read.
+pub fn capture(
+    vendor_id: u16,
+    product_id: u16,
+    output: Option<&str>,
+    count: Option<u64>,
+) -> Result<(), Box<dyn Error>> {
+    let mut writer: Box<dyn Write> = match output {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(io::stdout()),
+    };
+    let start = Instant::now();
+    let mut captured = 0;
+    let mut write_error = None;
+    read_reports(vendor_id, product_id, |report| {
+        let bytes: Vec<String> = report.iter().map(|b| format!("{:02x}", b)).collect();
+        if let Err(e) = writeln!(
+            writer,
+            "{} {}",
+            start.elapsed().as_millis(),
+            bytes.join(" ")
+        ) {
+            write_error = Some(e);
+            return false;
+        }
+        captured += 1;
+        count.is_none_or(|count| captured < count)
+    })?;
+    match write_error {
+        Some(e) => Err(e.into()),
+        None => Ok(()),
+    }
+}
+
+/// Prints raw reports as they arrive, leaving out repeats of the previous one.
+pub fn monitor(vendor_id: u16, product_id: u16) -> Result<(), Box<dyn Error>> {
+    let mut out = io::stdout();
+    let mut previous = Vec::new();
+    let mut write_error = None;
+    read_reports(vendor_id, product_id, |report| {
+        if report != previous.as_slice() {
+            if let Err(e) = writeln!(out, "{:?}", report) {
+                write_error = Some(e);
+                return false;
+            }
+            previous = report.to_vec();
+        }
+        true
+    })?;
+    match write_error {
+        Some(e) => Err(e.into()),
+        None => Ok(()),
+    }
+}
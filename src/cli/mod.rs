@@ -0,0 +1,3 @@
+pub mod hid;
+pub mod practice;
+pub mod stats;
@@ -0,0 +1,168 @@
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor};
+use crossterm::terminal::{self, Clear, ClearType};
+use crossterm::{cursor, execute, queue};
+use moonlander_trainer::session::{Input, Key, KeyResult, TypingSession};
+use moonlander_trainer::TypingPatternGenerator;
+use std::error::Error;
+use std::fs;
+use std::io::{self, Write};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often the watched files are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+type Load<'a> = Box<dyn FnMut() -> Result<Arc<dyn TypingPatternGenerator>, Box<dyn Error>> + 'a>;
+
+/// Rebuilds the generator of a session when one of the files it was built from changes.
+pub struct Reload<'a> {
+    paths: Vec<String>,
+    modified: Vec<Option<SystemTime>>,
+    load: Load<'a>,
+}
+
+impl<'a> Reload<'a> {
+    pub fn new(
+        paths: Vec<String>,
+        load: impl FnMut() -> Result<Arc<dyn TypingPatternGenerator>, Box<dyn Error>> + 'a,
+    ) -> Self {
+        let modified = paths.iter().map(|path| modified(path)).collect();
+        Reload {
+            paths,
+            modified,
+            load: Box::new(load),
+        }
+    }
+
+    /// Loads the generator again if a file changed since the last call, and returns the result.
+    fn poll(&mut self) -> Option<Result<Arc<dyn TypingPatternGenerator>, Box<dyn Error>>> {
+        let modified: Vec<_> = self.paths.iter().map(|path| modified(path)).collect();
+        if modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        Some((self.load)())
+    }
+}
+
+fn modified(path: &str) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Runs a session in the terminal until `drills` drills are completed or Escape is pressed.
+///
+/// With `reload`, the generator is replaced whenever the watched files change. A configuration
+/// that fails to load is reported in the status line and the previous generator is kept.
+pub fn practice(
+    session: &mut TypingSession,
+    drills: u32,
+    reload: Option<Reload>,
+) -> io::Result<()> {
+    terminal::enable_raw_mode()?;
+    let result = run(session, drills, reload);
+    terminal::disable_raw_mode()?;
+    execute!(io::stdout(), ResetColor, Print("\n"))?;
+    result
+}
+
+fn run(session: &mut TypingSession, drills: u32, mut reload: Option<Reload>) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    let mut last_key: Option<Instant> = None;
+    let mut status = String::new();
+    let mut redraw = true;
+    while session.stats.completed < drills {
+        if redraw {
+            render(&mut stdout, session, &status)?;
+            redraw = false;
+        }
+        if !event::poll(POLL_INTERVAL)? {
+            if let Some(result) = reload.as_mut().and_then(Reload::poll) {
+                status = match result {
+                    Ok(generator) => {
+                        session.set_generator(generator);
+                        String::from("Reloaded, the next drill uses the new configuration.")
+                    }
+                    Err(e) => format!("Kept the previous configuration: {}", e),
+                };
+                redraw = true;
+            }
+            continue;
+        }
+        redraw = true;
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event::read()?
+        else {
+            continue;
+        };
+        let key = match code {
+            KeyCode::Esc => break,
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => break,
+            KeyCode::Char(c) => Key::Char(c),
+            KeyCode::Enter => Key::Char('\n'),
+            // Tab types a tab where one is expected and moves over auto-inserted closers otherwise.
+            KeyCode::Tab => match session.drill().chars.get(session.position()) {
+                Some(c) if c.c == '\t' && c.input == Input::Typed => Key::Char('\t'),
+                _ => Key::Skip,
+            },
+            KeyCode::Backspace => Key::Backspace,
+            _ => continue,
+        };
+        // The first key has nothing to be timed against.
+        let elapsed_ms = last_key.map_or(0, |t| t.elapsed().as_millis() as u64);
+        last_key = Some(Instant::now());
+        if session.press(key, elapsed_ms) == KeyResult::Completed {
+            status.clear();
+        }
+    }
+    Ok(())
+}
+
+fn render(stdout: &mut impl Write, session: &TypingSession, status: &str) -> io::Result<()> {
+    let drill = session.drill();
+    let position = session.position();
+    let pending = session.pending_closers();
+    let stats = &session.stats;
+    queue!(
+        stdout,
+        Clear(ClearType::All),
+        cursor::MoveTo(0, 0),
+        Print(format!(
+            "{}  drills {}  keystrokes {}  errors {}\r\n\r\n",
+            drill.name, stats.completed, stats.keystrokes, stats.errors
+        ))
+    )?;
+    for (i, c) in drill.chars.iter().enumerate() {
+        let color = if i < position {
+            Color::Green
+        } else if i == position && session.mistyped() {
+            Color::Red
+        } else if pending.contains(&i) {
+            Color::DarkGrey
+        } else {
+            Color::Reset
+        };
+        queue!(stdout, SetForegroundColor(color))?;
+        if i == position {
+            queue!(stdout, SetAttribute(Attribute::Reverse))?;
+        }
+        match c.c {
+            '\n' if i == position => queue!(stdout, Print("↵"))?,
+            '\n' => {}
+            c => queue!(stdout, Print(c))?,
+        }
+        queue!(stdout, SetAttribute(Attribute::NoReverse))?;
+        if c.c == '\n' {
+            queue!(stdout, Print("\r\n"))?;
+        }
+    }
+    queue!(stdout, ResetColor, Print("\r\n\r\nEsc to stop"))?;
+    if !status.is_empty() {
+        queue!(stdout, Print(format!("\r\n{}", status)))?;
+    }
+    stdout.flush()
+}
@@ -0,0 +1,87 @@
+use moonlander_trainer::session::log::SessionLog;
+use moonlander_trainer::session::ngrams::NgramStats;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+#[derive(Debug, Default)]
+struct Totals {
+    keystrokes: u32,
+    errors: u32,
+    total_ms: u64,
+}
+
+impl Totals {
+    fn accuracy(&self) -> f64 {
+        100.0 * (1.0 - self.errors as f64 / self.keystrokes.max(1) as f64)
+    }
+
+    fn mean_ms(&self) -> f64 {
+        self.total_ms as f64 / self.keystrokes.max(1) as f64
+    }
+
+    /// Words per minute, counting five keystrokes as a word.
+    fn wpm(&self) -> f64 {
+        60_000.0 / (5.0 * self.mean_ms().max(1.0))
+    }
+}
+
+/// Prints speed and accuracy overall and per pattern, and the weakest n-grams of the given sizes.
+pub fn print_stats(
+    out: &mut impl Write,
+    log: &SessionLog,
+    sizes: &[usize],
+    weakest: usize,
+    min_samples: u32,
+) -> io::Result<()> {
+    let mut total = Totals::default();
+    let mut patterns: BTreeMap<&str, Totals> = BTreeMap::new();
+    for event in &log.events {
+        for totals in [&mut total, patterns.entry(&event.pattern).or_default()] {
+            totals.keystrokes += 1;
+            totals.total_ms += event.elapsed_ms;
+            if event.is_error() {
+                totals.errors += 1;
+            }
+        }
+    }
+    writeln!(
+        out,
+        "{} keystrokes, {} errors, {:.1}% accuracy, {:.0} wpm",
+        total.keystrokes,
+        total.errors,
+        total.accuracy(),
+        total.wpm()
+    )?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "{:<30} {:>10} {:>8} {:>9} {:>6}",
+        "Pattern", "keystrokes", "errors", "accuracy", "wpm"
+    )?;
+    for (pattern, totals) in &patterns {
+        writeln!(
+            out,
+            "{:<30} {:>10} {:>8} {:>8.1}% {:>6.0}",
+            pattern,
+            totals.keystrokes,
+            totals.errors,
+            totals.accuracy(),
+            totals.wpm()
+        )?;
+    }
+
+    let ngrams = NgramStats::from_log(log, sizes);
+    writeln!(out)?;
+    writeln!(out, "Weakest n-grams:")?;
+    for (ngram, stat) in ngrams.weakest(weakest, min_samples) {
+        writeln!(
+            out,
+            "  {:<8} {:>5} times {:>7.0} ms {:>5.1}% errors",
+            format!("{:?}", ngram),
+            stat.count,
+            stat.mean_ms(),
+            100.0 * stat.error_rate()
+        )?;
+    }
+    Ok(())
+}
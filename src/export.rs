@@ -0,0 +1,186 @@
+use crate::generators::graph::GeneratorGraph;
+use crate::generators::random;
+use crate::generators::snapshot::GraphSnapshot;
+use crate::generators::{TypingPattern, TypingPatternGenerator};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// One pattern per line, or separated by blank lines if some span several lines.
+    Text,
+    /// The drill set with its provenance and how every pattern was put together.
+    Json,
+    /// A single line for Monkeytype's custom text, one phrase per pattern.
+    Monkeytype,
+    /// A worksheet to print.
+    Markdown,
+    /// A worksheet to print, as a standalone page.
+    Html,
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(ExportFormat::Text),
+            "json" => Ok(ExportFormat::Json),
+            "monkeytype" => Ok(ExportFormat::Monkeytype),
+            "markdown" => Ok(ExportFormat::Markdown),
+            "html" => Ok(ExportFormat::Html),
+            _ => Err(format!(
+                "expected text, json, monkeytype, markdown or html, got {:?}",
+                s
+            )),
+        }
+    }
+}
+
+/// Patterns generated in one go, with what it takes to generate them again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DrillSet {
+    pub generator: String,
+    pub seed: Option<u64>,
+    /// The version of the trainer that generated the patterns.
+    pub version: String,
+    /// Seconds since the Unix epoch.
+    pub created: u64,
+    /// The generator and everything it uses, if it can be serialized. Loading it as a graph
+    /// and generating from the root with the same seed gives the same patterns.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub graph: Option<GraphSnapshot>,
+    pub patterns: Vec<TypingPattern>,
+}
+
+impl DrillSet {
+    /// Generates `count` patterns, seeding the random number generator first if `seed` is given.
+    pub fn generate(
+        generator: &dyn TypingPatternGenerator,
+        count: usize,
+        seed: Option<u64>,
+    ) -> Self {
+        if let Some(seed) = seed {
+            random::seed(seed);
+        }
+        DrillSet {
+            generator: generator.name(),
+            seed,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            created: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            graph: None,
+            patterns: (0..count).map(|_| generator.generate()).collect(),
+        }
+    }
+
+    /// Records the generator as the root of a graph, unless it can't be serialized.
+    pub fn with_graph(mut self, generator: &Arc<dyn TypingPatternGenerator>) -> Self {
+        self.graph = GeneratorGraph::new(generator.clone(), Vec::new())
+            .snapshot()
+            .ok();
+        self
+    }
+
+    pub fn export(&self, format: ExportFormat) -> String {
+        match format {
+            ExportFormat::Text => self.to_text(),
+            ExportFormat::Json => {
+                serde_json::to_string_pretty(self).expect("drill sets have string keys only")
+            }
+            ExportFormat::Monkeytype => self.to_monkeytype(),
+            ExportFormat::Markdown => self.to_markdown(),
+            ExportFormat::Html => self.to_html(),
+        }
+    }
+
+    fn to_text(&self) -> String {
+        let separator = if self.patterns.iter().any(|p| p.pattern.contains('\n')) {
+            "\n\n"
+        } else {
+            "\n"
+        };
+        let mut text = self.texts().join(separator);
+        text.push('\n');
+        text
+    }
+
+    /// Patterns are separated by `|`, so turn on Monkeytype's pipe delimiter to keep each one
+    /// a single "word", and turn off its randomization to keep them in order. Monkeytype can't
+    /// show indentation, so every run of whitespace becomes one space. A pattern containing a
+    /// `|` is still split there.
+    fn to_monkeytype(&self) -> String {
+        let phrases: Vec<String> = self
+            .patterns
+            .iter()
+            .map(|p| p.pattern.split_whitespace().collect::<Vec<_>>().join(" "))
+            .collect();
+        format!("{}\n", phrases.join("|"))
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut markdown = format!("# {}\n\n{}\n", self.generator, self.provenance());
+        for (i, text) in self.texts().iter().enumerate() {
+            // The fence has to be longer than any run of backticks in the pattern.
+            let longest_run = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+            let fence = "`".repeat((longest_run + 1).max(3));
+            write!(
+                markdown,
+                "\n**{}.**\n\n{}\n{}\n{}\n",
+                i + 1,
+                fence,
+                text,
+                fence
+            )
+            .unwrap();
+        }
+        markdown
+    }
+
+    fn to_html(&self) -> String {
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+             <style>\n\
+             body {{ font-family: sans-serif; max-width: 50em; margin: 2em auto; }}\n\
+             pre {{ font-size: 1.2em; tab-size: 4; padding: 0.5em; border: 1px solid #ccc; }}\n\
+             li {{ break-inside: avoid; margin-bottom: 1em; }}\n\
+             </style>\n</head>\n<body>\n<h1>{title}</h1>\n<p>{provenance}</p>\n<ol>\n",
+            title = escape_html(&self.generator),
+            provenance = escape_html(&self.provenance()),
+        );
+        for text in self.texts() {
+            writeln!(html, "<li><pre>{}</pre></li>", escape_html(text)).unwrap();
+        }
+        html.push_str("</ol>\n</body>\n</html>\n");
+        html
+    }
+
+    fn texts(&self) -> Vec<&str> {
+        self.patterns.iter().map(|p| p.pattern.as_str()).collect()
+    }
+
+    fn provenance(&self) -> String {
+        let seed = match self.seed {
+            Some(seed) => format!("seed {}", seed),
+            None => String::from("no seed"),
+        };
+        format!(
+            "{} patterns of {}, {}, moonlander-trainer {}",
+            self.patterns.len(),
+            self.generator,
+            seed,
+            self.version
+        )
+    }
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
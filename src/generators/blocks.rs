@@ -0,0 +1,138 @@
+use crate::generators::error::GeneratorError;
+use crate::generators::helpers::{check_options, check_range, config_range, config_value};
+use crate::generators::random;
+use crate::generators::snapshot::GeneratorSnapshot;
+use crate::generators::{joined_length, TypingPattern, TypingPatternGenerator};
+use rand::Rng;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Indents every non-empty line of `text` by one level, i.e. one tab.
+pub fn indent(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("\t{}", line)
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Generates a multi-line block such as an `if (...) {` body `}` or a `match` arm list:
+///
+/// ```text
+/// header {
+///     line
+///     line
+/// }
+/// ```
+///
+/// Between `min_lines` and `max_lines` (inclusive) outputs of `body` are indented by one level,
+/// so blocks nest when `body` produces blocks itself. `open` and `close` default to `{` and `}`.
+#[derive(Debug, Clone)]
+pub struct BlockGenerator {
+    pub name: String,
+    pub header: Arc<dyn TypingPatternGenerator>,
+    pub body: Arc<dyn TypingPatternGenerator>,
+    pub min_lines: u32,
+    pub max_lines: u32,
+    pub open: String,
+    pub close: String,
+}
+
+impl BlockGenerator {
+    pub fn new(
+        name: &'static str,
+        header: Arc<dyn TypingPatternGenerator>,
+        body: Arc<dyn TypingPatternGenerator>,
+        config: HashMap<&str, String>,
+    ) -> Result<Self, GeneratorError> {
+        check_options(name, &config, &["min_lines", "max_lines", "open", "close"])?;
+        let (min_lines, max_lines) =
+            config_range(name, &config, ("min_lines", "max_lines"), (1, 3))?;
+        Ok(BlockGenerator {
+            name: name.to_string(),
+            header,
+            body,
+            min_lines,
+            max_lines,
+            open: config_value(name, &config, "open", String::from("{"))?,
+            close: config_value(name, &config, "close", String::from("}"))?,
+        })
+    }
+}
+
+impl TypingPatternGenerator for BlockGenerator {
+    fn generate(&self) -> TypingPattern {
+        let mut rng = random::rng();
+        let mut children = vec![self.header.generate()];
+        for _ in 0..rng.gen_range(self.min_lines..=self.max_lines) {
+            children.push(self.body.generate());
+        }
+        let mut lines = vec![format!("{} {}", children[0].pattern, self.open)];
+        lines.extend(children[1..].iter().map(|line| indent(&line.pattern)));
+        lines.push(self.close.clone());
+
+        TypingPattern {
+            name: self.name.clone(),
+            pattern: lines.join("\n"),
+            children,
+        }
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn snapshot(&self, children: &[usize]) -> Option<GeneratorSnapshot> {
+        Some(GeneratorSnapshot::Block {
+            name: self.name.clone(),
+            header: children[0],
+            body: children[1],
+            min_lines: self.min_lines,
+            max_lines: self.max_lines,
+            open: self.open.clone(),
+            close: self.close.clone(),
+        })
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("min_lines", self.min_lines.to_string()),
+            ("max_lines", self.max_lines.to_string()),
+            ("open", format!("{:?}", self.open)),
+            ("close", format!("{:?}", self.close)),
+        ]
+    }
+
+    fn children(&self) -> Vec<Arc<dyn TypingPatternGenerator>> {
+        vec![self.header.clone(), self.body.clone()]
+    }
+
+    fn min_length(&self, children: &[usize]) -> usize {
+        // Each body line comes after a newline.
+        let body = joined_length(self.min_lines as usize, children[1].saturating_add(1), "");
+        let fixed = self.open.chars().count() + self.close.chars().count() + 2;
+        children[0].saturating_add(body).saturating_add(fixed)
+    }
+
+    fn check(&self) -> Result<(), GeneratorError> {
+        check_range(
+            &self.name,
+            ("min_lines", "max_lines"),
+            &self.min_lines,
+            &self.max_lines,
+        )
+    }
+
+    fn with_range(&self, min: u32, max: u32) -> Option<Arc<dyn TypingPatternGenerator>> {
+        Some(Arc::new(BlockGenerator {
+            min_lines: min,
+            max_lines: max,
+            ..self.clone()
+        }))
+    }
+}
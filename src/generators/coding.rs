@@ -1,83 +1,664 @@
-use crate::generators::randomized::OneOfStringsPatternGenerator;
-use crate::generators::sequences::RandomRepeatGenerator;
+use crate::generators::blocks::BlockGenerator;
+use crate::generators::error::GeneratorError;
+use crate::generators::expressions::ExpressionGenerator;
+use crate::generators::graph::GeneratorGraph;
+use crate::generators::helpers::{
+    check_options, check_probability, check_range, config_probability, config_range, config_value,
+};
+use crate::generators::nesting::BalancedNestingGenerator;
+use crate::generators::random::{self, GeneratorRng};
+use crate::generators::randomized::{OneOfStringsPatternGenerator, WeightedPatternGenerator};
+use crate::generators::sequences::{RandomRepeatGenerator, RepeatPatternGenerator};
 use crate::generators::simple::{ListOfPatternsGenerator, SingleStringGenerator};
+use crate::generators::snapshot::GeneratorSnapshot;
 use crate::generators::{TypingPattern, TypingPatternGenerator};
-use rand::{thread_rng, Rng};
+use rand::prelude::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::rc::Rc;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NumberFormat {
+    Decimal,
+    Hex,
+    Binary,
+    Octal,
+    Float,
+    Scientific,
+}
+
+impl NumberFormat {
+    fn radix(&self) -> u32 {
+        match self {
+            NumberFormat::Hex => 16,
+            NumberFormat::Binary => 2,
+            NumberFormat::Octal => 8,
+            NumberFormat::Decimal | NumberFormat::Float | NumberFormat::Scientific => 10,
+        }
+    }
+
+    fn prefix(&self) -> &'static str {
+        match self {
+            NumberFormat::Hex => "0x",
+            NumberFormat::Binary => "0b",
+            NumberFormat::Octal => "0o",
+            NumberFormat::Decimal | NumberFormat::Float | NumberFormat::Scientific => "",
+        }
+    }
+}
+
+impl FromStr for NumberFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "decimal" => Ok(NumberFormat::Decimal),
+            "hex" => Ok(NumberFormat::Hex),
+            "binary" => Ok(NumberFormat::Binary),
+            "octal" => Ok(NumberFormat::Octal),
+            "float" => Ok(NumberFormat::Float),
+            "scientific" => Ok(NumberFormat::Scientific),
+            _ => Err(format!("unknown number format {:?}", s)),
+        }
+    }
+}
+
+impl fmt::Display for NumberFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let format = match self {
+            NumberFormat::Decimal => "decimal",
+            NumberFormat::Hex => "hex",
+            NumberFormat::Binary => "binary",
+            NumberFormat::Octal => "octal",
+            NumberFormat::Float => "float",
+            NumberFormat::Scientific => "scientific",
+        };
+        write!(f, "{}", format)
+    }
+}
+
+/// Generates numbers such as `4711`, `0x1F`, `0b1010`, `-3.25`, `6.02e23`, `1_000_000` or `42u32`.
+///
+/// `min_length` and `max_length` bound the number of digits (of the integer part for floats), inclusive.
+/// Setting `min_value` and `max_value` draws integers from that range instead.
+#[derive(Debug, Clone)]
 pub struct NumberPatternGenerator {
-    name: &'static str,
-    min_length: u32,
-    max_length: u32,
+    pub name: String,
+    pub format: NumberFormat,
+    pub min_length: u32,
+    pub max_length: u32,
+    pub range: Option<(i64, i64)>,
+    pub leading_zeros: bool,
+    pub uppercase: bool,
+    pub negative_probability: f64,
+    pub min_fraction_length: u32,
+    pub max_fraction_length: u32,
+    pub min_exponent: i32,
+    pub max_exponent: i32,
+    pub digit_separator: String,
+    pub separator_group: usize,
+    pub suffixes: Vec<String>,
 }
 
 impl NumberPatternGenerator {
-    pub fn new(name: &'static str, config: HashMap<&str, String>) -> Self {
-        let mut min_length: u32 = 4;
-        let mut max_length: u32 = 8;
-        config
-            .get("min_length")
-            .map(|s| min_length = s.parse().unwrap());
-        config
-            .get("max_length")
-            .map(|s| max_length = s.parse().unwrap());
-        NumberPatternGenerator {
+    pub fn new(name: &'static str, config: HashMap<&str, String>) -> Result<Self, GeneratorError> {
+        check_options(
             name,
+            &config,
+            &[
+                "format",
+                "min_length",
+                "max_length",
+                "min_value",
+                "max_value",
+                "leading_zeros",
+                "uppercase",
+                "negative_probability",
+                "min_fraction_length",
+                "max_fraction_length",
+                "min_exponent",
+                "max_exponent",
+                "digit_separator",
+                "separator_group",
+                "suffixes",
+            ],
+        )?;
+        let format: NumberFormat = config_value(name, &config, "format", NumberFormat::Decimal)?;
+        let range = match (config.get("min_value"), config.get("max_value")) {
+            (None, None) => None,
+            _ => Some(config_range(
+                name,
+                &config,
+                ("min_value", "max_value"),
+                (0, i64::MAX),
+            )?),
+        };
+        let default_group = match format {
+            NumberFormat::Hex | NumberFormat::Binary => 4,
+            _ => 3,
+        };
+        let (min_length, max_length) =
+            config_range(name, &config, ("min_length", "max_length"), (4, 8))?;
+        let (min_fraction_length, max_fraction_length) = config_range(
+            name,
+            &config,
+            ("min_fraction_length", "max_fraction_length"),
+            (1, 3),
+        )?;
+        let (min_exponent, max_exponent) =
+            config_range(name, &config, ("min_exponent", "max_exponent"), (-10, 10))?;
+        Ok(NumberPatternGenerator {
+            name: name.to_string(),
+            format,
             min_length,
             max_length,
+            range,
+            leading_zeros: config_value(name, &config, "leading_zeros", false)?,
+            uppercase: config_value(name, &config, "uppercase", false)?,
+            negative_probability: config_probability(name, &config, "negative_probability", 0.0)?,
+            min_fraction_length,
+            max_fraction_length,
+            min_exponent,
+            max_exponent,
+            digit_separator: config_value(name, &config, "digit_separator", String::new())?,
+            separator_group: config_value(name, &config, "separator_group", default_group)?,
+            suffixes: config
+                .get("suffixes")
+                .map(|s| s.split(',').map(|x| x.trim().to_string()).collect())
+                .unwrap_or_default(),
+        })
+    }
+
+    fn random_digits(&self, rng: &mut GeneratorRng, length: u32, leading_zeros: bool) -> String {
+        (0..length)
+            .map(|i| {
+                let first = if i == 0 && !leading_zeros && length > 1 {
+                    1
+                } else {
+                    0
+                };
+                let digit = rng.gen_range(first..self.format.radix());
+                self.digit_char(digit)
+            })
+            .collect()
+    }
+
+    fn digit_char(&self, digit: u32) -> char {
+        let c = char::from_digit(digit, self.format.radix()).unwrap();
+        if self.uppercase {
+            c.to_ascii_uppercase()
+        } else {
+            c
+        }
+    }
+
+    /// Inserts `digit_separator` between groups of digits, counted from the right.
+    fn separate(&self, digits: &str) -> String {
+        if self.digit_separator.is_empty() || self.separator_group == 0 {
+            return digits.to_string();
         }
+        let chars: Vec<char> = digits.chars().collect();
+        chars
+            .rchunks(self.separator_group)
+            .rev()
+            .map(|group| group.iter().collect::<String>())
+            .collect::<Vec<String>>()
+            .join(&self.digit_separator)
     }
 }
 
 impl TypingPatternGenerator for NumberPatternGenerator {
     fn generate(&self) -> TypingPattern {
         let mut pattern = String::new();
-        let mut rng = thread_rng();
-        let length = rng.gen_range(self.min_length..self.max_length);
-        for _ in 0..length {
-            pattern.push(rng.gen_range(0..10).to_string().chars().next().unwrap());
+        self.generate_into(&mut pattern);
+        TypingPattern {
+            name: self.name.clone(),
+            pattern,
+            children: Vec::new(),
+        }
+    }
+
+    fn generate_into(&self, pattern: &mut String) {
+        let mut rng = random::rng();
+        let mut negative = rng.gen_bool(self.negative_probability);
+        let integer_digits = match self.range {
+            Some((min_value, max_value)) => {
+                let value = rng.gen_range(min_value..=max_value);
+                negative = value < 0;
+                let mut magnitude = value.unsigned_abs();
+                let mut digits = Vec::new();
+                loop {
+                    digits.push(self.digit_char((magnitude % self.format.radix() as u64) as u32));
+                    magnitude /= self.format.radix() as u64;
+                    if magnitude == 0 {
+                        break;
+                    }
+                }
+                digits.iter().rev().collect()
+            }
+            None => match self.format {
+                // Normalized notation has a single nonzero digit before the point.
+                NumberFormat::Scientific => self
+                    .digit_char(rng.gen_range(1..self.format.radix()))
+                    .to_string(),
+                _ => {
+                    let length = rng.gen_range(self.min_length..=self.max_length);
+                    self.random_digits(&mut rng, length, self.leading_zeros)
+                }
+            },
+        };
+
+        if negative {
+            pattern.push('-');
+        }
+        pattern.push_str(self.format.prefix());
+        pattern.push_str(&self.separate(&integer_digits));
+        if matches!(self.format, NumberFormat::Float | NumberFormat::Scientific) {
+            let length = rng.gen_range(self.min_fraction_length..=self.max_fraction_length);
+            pattern.push('.');
+            pattern.push_str(&self.random_digits(&mut rng, length, true));
+        }
+        if self.format == NumberFormat::Scientific {
+            pattern.push('e');
+            pattern.push_str(
+                &rng.gen_range(self.min_exponent..=self.max_exponent)
+                    .to_string(),
+            );
+        }
+        if let Some(suffix) = self.suffixes.choose(&mut rng) {
+            pattern.push_str(suffix);
         }
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn snapshot(&self, _children: &[usize]) -> Option<GeneratorSnapshot> {
+        Some(GeneratorSnapshot::Number {
+            name: self.name.clone(),
+            format: self.format,
+            min_length: self.min_length,
+            max_length: self.max_length,
+            range: self.range,
+            leading_zeros: self.leading_zeros,
+            uppercase: self.uppercase,
+            negative_probability: self.negative_probability,
+            min_fraction_length: self.min_fraction_length,
+            max_fraction_length: self.max_fraction_length,
+            min_exponent: self.min_exponent,
+            max_exponent: self.max_exponent,
+            digit_separator: self.digit_separator.clone(),
+            separator_group: self.separator_group,
+            suffixes: self.suffixes.clone(),
+        })
+    }
+
+    fn check(&self) -> Result<(), GeneratorError> {
+        check_range(
+            &self.name,
+            ("min_length", "max_length"),
+            &self.min_length,
+            &self.max_length,
+        )?;
+        check_range(
+            &self.name,
+            ("min_fraction_length", "max_fraction_length"),
+            &self.min_fraction_length,
+            &self.max_fraction_length,
+        )?;
+        check_range(
+            &self.name,
+            ("min_exponent", "max_exponent"),
+            &self.min_exponent,
+            &self.max_exponent,
+        )?;
+        if let Some((min, max)) = self.range {
+            check_range(&self.name, ("min_value", "max_value"), &min, &max)?;
+        }
+        check_probability(
+            &self.name,
+            "negative_probability",
+            self.negative_probability,
+        )
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        let mut parameters = vec![
+            ("format", self.format.to_string()),
+            ("min_length", self.min_length.to_string()),
+            ("max_length", self.max_length.to_string()),
+            ("leading_zeros", self.leading_zeros.to_string()),
+            ("uppercase", self.uppercase.to_string()),
+            (
+                "negative_probability",
+                self.negative_probability.to_string(),
+            ),
+            ("min_fraction_length", self.min_fraction_length.to_string()),
+            ("max_fraction_length", self.max_fraction_length.to_string()),
+            ("min_exponent", self.min_exponent.to_string()),
+            ("max_exponent", self.max_exponent.to_string()),
+            ("digit_separator", format!("{:?}", self.digit_separator)),
+            ("separator_group", self.separator_group.to_string()),
+            ("suffixes", format!("{:?}", self.suffixes)),
+        ];
+        if let Some((min, max)) = self.range {
+            parameters.push(("min_value", min.to_string()));
+            parameters.push(("max_value", max.to_string()));
+        }
+        parameters
+    }
+
+    /// Sets the range of values if there is one, or else the number of digits.
+    fn with_range(&self, min: u32, max: u32) -> Option<Arc<dyn TypingPatternGenerator>> {
+        let mut generator = self.clone();
+        match generator.range {
+            Some(_) => generator.range = Some((i64::from(min), i64::from(max))),
+            None => (generator.min_length, generator.max_length) = (min, max),
+        }
+        Some(Arc::new(generator))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StringLiteralStyle {
+    /// `'foo'`
+    Single,
+    /// `"foo"`
+    Double,
+    /// JavaScript template literals, `` `foo ${bar}` ``
+    Backtick,
+    /// Rust raw strings, `r#"foo"#`
+    Raw,
+    /// Python f-strings, `f"foo {bar}"`
+    FString,
+}
+
+impl FromStr for StringLiteralStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "single" => Ok(StringLiteralStyle::Single),
+            "double" => Ok(StringLiteralStyle::Double),
+            "backtick" => Ok(StringLiteralStyle::Backtick),
+            "raw" => Ok(StringLiteralStyle::Raw),
+            "fstring" => Ok(StringLiteralStyle::FString),
+            _ => Err(format!("unknown string literal style {:?}", s)),
+        }
+    }
+}
+
+impl fmt::Display for StringLiteralStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let style = match self {
+            StringLiteralStyle::Single => "single",
+            StringLiteralStyle::Double => "double",
+            StringLiteralStyle::Backtick => "backtick",
+            StringLiteralStyle::Raw => "raw",
+            StringLiteralStyle::FString => "fstring",
+        };
+        write!(f, "{}", style)
+    }
+}
+
+/// Wraps the output of `pattern` in a string literal of one of the comma-separated `styles`.
+///
+/// Quotes and backslashes in the content are escaped as the style requires. With `escape_probability`
+/// an escape sequence like `\n` replaces a space, and with `interpolation_probability` template
+/// literals and f-strings get an interpolation of `interpolation` (or `pattern` if there is none).
+#[derive(Debug)]
+pub struct StringLiteralGenerator {
+    pub name: String,
+    pub pattern: Arc<dyn TypingPatternGenerator>,
+    pub interpolation: Option<Arc<dyn TypingPatternGenerator>>,
+    pub styles: Vec<StringLiteralStyle>,
+    pub escape_probability: f64,
+    pub interpolation_probability: f64,
+}
+
+impl StringLiteralGenerator {
+    pub fn new(
+        name: &'static str,
+        child: Arc<dyn TypingPatternGenerator>,
+        interpolation: Option<Arc<dyn TypingPatternGenerator>>,
+        config: HashMap<&str, String>,
+    ) -> Result<Self, GeneratorError> {
+        check_options(
+            name,
+            &config,
+            &["styles", "escape_probability", "interpolation_probability"],
+        )?;
+        let styles = config_value(
+            name,
+            &config,
+            "styles",
+            String::from("single,double,backtick,raw,fstring"),
+        )?
+        .split(',')
+        .map(|s| {
+            s.trim()
+                .parse()
+                .map_err(|reason| GeneratorError::InvalidOption {
+                    generator: name.to_string(),
+                    option: String::from("styles"),
+                    value: s.to_string(),
+                    reason,
+                })
+        })
+        .collect::<Result<Vec<StringLiteralStyle>, GeneratorError>>()?;
+        Ok(StringLiteralGenerator {
+            name: name.to_string(),
+            pattern: child,
+            interpolation,
+            styles,
+            escape_probability: config_probability(name, &config, "escape_probability", 0.3)?,
+            interpolation_probability: config_probability(
+                name,
+                &config,
+                "interpolation_probability",
+                0.5,
+            )?,
+        })
+    }
+
+    /// Replaces a random space in `content` with `insertion` surrounded by `separator`,
+    /// or appends it if there is none.
+    fn insert_at_space(
+        rng: &mut GeneratorRng,
+        content: &mut String,
+        insertion: &str,
+        separator: &str,
+    ) {
+        let spaces: Vec<usize> = content.match_indices(' ').map(|(i, _)| i).collect();
+        match spaces.choose(rng) {
+            Some(&i) => content.replace_range(
+                i..i + 1,
+                &format!("{}{}{}", separator, insertion, separator),
+            ),
+            None if content.is_empty() => content.push_str(insertion),
+            None => content.push_str(&format!("{}{}", separator, insertion)),
+        }
+    }
+}
+
+impl TypingPatternGenerator for StringLiteralGenerator {
+    fn generate(&self) -> TypingPattern {
+        let mut rng = random::rng();
+        let generated = self.pattern.generate();
+        let content = &generated.pattern;
+        let style = *self.styles.choose(&mut rng).unwrap();
+
+        let pattern = if style == StringLiteralStyle::Raw {
+            // Raw strings need one more `#` than the longest run of them after a quote in the content.
+            let mut hashes = String::from("#");
+            while content.contains(&format!("\"{}", hashes)) {
+                hashes.push('#');
+            }
+            format!("r{}\"{}\"{}", hashes, content, hashes)
+        } else {
+            let (prefix, quote) = match style {
+                StringLiteralStyle::Single => ("", '\''),
+                StringLiteralStyle::Double => ("", '"'),
+                StringLiteralStyle::Backtick => ("", '`'),
+                _ => ("f", '"'),
+            };
+            let mut escaped = String::new();
+            let mut chars = content.chars().peekable();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => escaped.push_str("\\\\"),
+                    // Only the interpolations inserted below are meant to be interpolated.
+                    '$' if style == StringLiteralStyle::Backtick && chars.peek() == Some(&'{') => {
+                        escaped.push_str("\\$")
+                    }
+                    '{' | '}' if style == StringLiteralStyle::FString => {
+                        escaped.push(c);
+                        escaped.push(c);
+                    }
+                    c if c == quote => {
+                        escaped.push('\\');
+                        escaped.push(c);
+                    }
+                    c => escaped.push(c),
+                }
+            }
+
+            if rng.gen_bool(self.escape_probability) {
+                let quote_escape = format!("\\{}", quote);
+                let escape = ["\\n", "\\t", "\\\\", &quote_escape]
+                    .choose(&mut rng)
+                    .unwrap()
+                    .to_string();
+                Self::insert_at_space(&mut rng, &mut escaped, &escape, "");
+            }
+            if matches!(
+                style,
+                StringLiteralStyle::Backtick | StringLiteralStyle::FString
+            ) && rng.gen_bool(self.interpolation_probability)
+            {
+                let value = self
+                    .interpolation
+                    .as_ref()
+                    .unwrap_or(&self.pattern)
+                    .generate()
+                    .pattern;
+                let interpolation = if style == StringLiteralStyle::Backtick {
+                    format!("${{{}}}", value)
+                } else {
+                    format!("{{{}}}", value)
+                };
+                Self::insert_at_space(&mut rng, &mut escaped, &interpolation, " ");
+            }
+            format!("{}{}{}{}", prefix, quote, escaped, quote)
+        };
 
         TypingPattern {
-            name: self.name.to_string(),
+            name: self.name.clone(),
             pattern,
+            children: vec![generated],
         }
     }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn snapshot(&self, children: &[usize]) -> Option<GeneratorSnapshot> {
+        Some(GeneratorSnapshot::StringLiteral {
+            name: self.name.clone(),
+            child: children[0],
+            interpolation: children.get(1).copied(),
+            styles: self.styles.clone(),
+            escape_probability: self.escape_probability,
+            interpolation_probability: self.interpolation_probability,
+        })
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        vec![
+            (
+                "styles",
+                self.styles
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<String>>()
+                    .join(","),
+            ),
+            ("escape_probability", self.escape_probability.to_string()),
+            (
+                "interpolation_probability",
+                self.interpolation_probability.to_string(),
+            ),
+        ]
+    }
+
+    fn children(&self) -> Vec<Arc<dyn TypingPatternGenerator>> {
+        let mut children = vec![self.pattern.clone()];
+        children.extend(self.interpolation.clone());
+        children
+    }
+
+    fn min_length(&self, children: &[usize]) -> usize {
+        // Interpolations are optional, but the content and the quotes are always there.
+        children[0].saturating_add(2)
+    }
+
+    fn check(&self) -> Result<(), GeneratorError> {
+        if self.styles.is_empty() {
+            return Err(GeneratorError::NoChoices {
+                generator: self.name.clone(),
+                reason: String::from("there are no styles"),
+            });
+        }
+        check_probability(&self.name, "escape_probability", self.escape_probability)?;
+        check_probability(
+            &self.name,
+            "interpolation_probability",
+            self.interpolation_probability,
+        )
+    }
 }
 
 pub struct CodingGenerators {
-    pub open_paren: Rc<dyn TypingPatternGenerator>,
-    pub close_paren: Rc<dyn TypingPatternGenerator>,
-    pub open_bracket: Rc<dyn TypingPatternGenerator>,
-    pub close_bracket: Rc<dyn TypingPatternGenerator>,
-    pub open_brace: Rc<dyn TypingPatternGenerator>,
-    pub close_brace: Rc<dyn TypingPatternGenerator>,
-    pub symbols: Rc<dyn TypingPatternGenerator>,
-    pub semicolon: Rc<dyn TypingPatternGenerator>,
-    pub number: Rc<dyn TypingPatternGenerator>,
-    pub number_list: Rc<dyn TypingPatternGenerator>,
-    pub array_deref: Rc<dyn TypingPatternGenerator>,
-}
-
-pub fn create_coding_generators() -> CodingGenerators {
-    let number = Rc::new(NumberPatternGenerator::new(
+    pub open_paren: Arc<dyn TypingPatternGenerator>,
+    pub close_paren: Arc<dyn TypingPatternGenerator>,
+    pub open_bracket: Arc<dyn TypingPatternGenerator>,
+    pub close_bracket: Arc<dyn TypingPatternGenerator>,
+    pub open_brace: Arc<dyn TypingPatternGenerator>,
+    pub close_brace: Arc<dyn TypingPatternGenerator>,
+    pub symbols: Arc<dyn TypingPatternGenerator>,
+    pub semicolon: Arc<dyn TypingPatternGenerator>,
+    pub number: Arc<dyn TypingPatternGenerator>,
+    pub number_list: Arc<dyn TypingPatternGenerator>,
+    pub array_deref: Arc<dyn TypingPatternGenerator>,
+    pub string_literal: Arc<dyn TypingPatternGenerator>,
+    pub balanced_brackets: Arc<dyn TypingPatternGenerator>,
+    pub closing_run: Arc<dyn TypingPatternGenerator>,
+}
+
+pub fn create_coding_generators() -> Result<CodingGenerators, GeneratorError> {
+    let number = Arc::new(NumberPatternGenerator::new(
         "number",
         HashMap::from([
             ("min_length", "3".to_string()),
-            ("max_length", "5".to_string()),
+            ("max_length", "4".to_string()),
         ]),
-    ));
-    let list_of_numbers = Rc::new(ListOfPatternsGenerator::new(
+    )?);
+    let list_of_numbers = Arc::new(ListOfPatternsGenerator::new(
         "list_of_numbers",
         vec![number.clone()],
         HashMap::new(),
-    ));
-    let camel_cased_symbols = Rc::new(RandomRepeatGenerator::new(
+    )?);
+    let camel_cased_symbols = Arc::new(RandomRepeatGenerator::new(
         "camel_cased_symbols",
-        Rc::new(OneOfStringsPatternGenerator::new(
-            "list_of_symbols",
+        Arc::new(OneOfStringsPatternGenerator::new(
+            "symbols",
             vec![
                 "previous",
                 "next",
@@ -97,20 +678,20 @@ pub fn create_coding_generators() -> CodingGenerators {
                 "property",
                 "variable",
             ],
-        )),
+        )?),
         HashMap::from([
             ("delimiter", "".to_string()),
             ("min_count", "1".to_string()),
             ("max_count", "3".to_string()),
             ("camel_case_strings", "true".to_string()),
         ]),
-    ));
+    )?);
 
-    let open_paren = Rc::new(SingleStringGenerator::new("open_paren", "("));
-    let close_paren = Rc::new(SingleStringGenerator::new("close_paren", ")"));
-    let open_bracket = Rc::new(SingleStringGenerator::new("open_bracket", "["));
-    let close_bracket = Rc::new(SingleStringGenerator::new("close_bracket", "]"));
-    let array_deref = Rc::new(ListOfPatternsGenerator::new(
+    let open_paren = Arc::new(SingleStringGenerator::new("open_paren", "("));
+    let close_paren = Arc::new(SingleStringGenerator::new("close_paren", ")"));
+    let open_bracket = Arc::new(SingleStringGenerator::new("open_bracket", "["));
+    let close_bracket = Arc::new(SingleStringGenerator::new("close_bracket", "]"));
+    let array_deref = Arc::new(ListOfPatternsGenerator::new(
         "array_deref",
         vec![
             camel_cased_symbols.clone(),
@@ -119,38 +700,65 @@ pub fn create_coding_generators() -> CodingGenerators {
             close_bracket.clone(),
         ],
         HashMap::from([("delimiter", "".to_string())]),
-    ));
-    CodingGenerators {
+    )?);
+    let string_literal = Arc::new(StringLiteralGenerator::new(
+        "string_literal",
+        camel_cased_symbols.clone(),
+        None,
+        HashMap::from([
+            ("styles", "single,double".to_string()),
+            ("escape_probability", "0".to_string()),
+        ]),
+    )?);
+    let balanced_brackets = Arc::new(BalancedNestingGenerator::new(
+        "balanced_brackets",
+        Some(camel_cased_symbols.clone()),
+        HashMap::new(),
+    )?);
+    let closing_run = Arc::new(BalancedNestingGenerator::new(
+        "closing_run",
+        None,
+        HashMap::from([
+            ("min_depth", "2".to_string()),
+            ("max_depth", "5".to_string()),
+            ("closing_runs", "true".to_string()),
+        ]),
+    )?);
+    Ok(CodingGenerators {
         open_paren,
         close_paren,
         open_bracket,
         close_bracket,
-        open_brace: Rc::new(SingleStringGenerator::new("open_brace", "{")),
-        close_brace: Rc::new(SingleStringGenerator::new("close_brace", "}")),
+        open_brace: Arc::new(SingleStringGenerator::new("open_brace", "{")),
+        close_brace: Arc::new(SingleStringGenerator::new("close_brace", "}")),
         symbols: camel_cased_symbols,
-        semicolon: Rc::new(SingleStringGenerator::new("semicolon", ";")),
+        semicolon: Arc::new(SingleStringGenerator::new("semicolon", ";")),
         number,
         number_list: list_of_numbers,
         array_deref,
-    }
+        string_literal,
+        balanced_brackets,
+        closing_run,
+    })
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn create_method_call_generator(
     name: &'static str,
-    method_name: Rc<dyn TypingPatternGenerator>,
-    open_paren: Rc<dyn TypingPatternGenerator>,
-    close_paren: Rc<dyn TypingPatternGenerator>,
-    argument: Rc<dyn TypingPatternGenerator>,
+    method_name: Arc<dyn TypingPatternGenerator>,
+    open_paren: Arc<dyn TypingPatternGenerator>,
+    close_paren: Arc<dyn TypingPatternGenerator>,
+    argument: Arc<dyn TypingPatternGenerator>,
     argument_delimiter: String,
     min_arguments: u32,
     max_arguments: u32,
-) -> Rc<dyn TypingPatternGenerator> {
-    return Rc::new(ListOfPatternsGenerator::new(
+) -> Result<Arc<dyn TypingPatternGenerator>, GeneratorError> {
+    Ok(Arc::new(ListOfPatternsGenerator::new(
         name,
         vec![
             method_name,
             open_paren,
-            Rc::new(RandomRepeatGenerator::new(
+            Arc::new(RandomRepeatGenerator::new(
                 "arguments",
                 argument,
                 HashMap::from([
@@ -158,9 +766,186 @@ pub fn create_method_call_generator(
                     ("min_count", min_arguments.to_string()),
                     ("max_count", max_arguments.to_string()),
                 ]),
-            )),
+            )?),
             close_paren,
         ],
         HashMap::from([("delimiter", String::from(""))]),
-    ));
+    )?))
+}
+
+/// Creates an `if (condition) {` block whose lines come from `statement`.
+pub fn create_if_block_generator(
+    name: &'static str,
+    condition: Arc<dyn TypingPatternGenerator>,
+    statement: Arc<dyn TypingPatternGenerator>,
+    min_lines: u32,
+    max_lines: u32,
+) -> Result<Arc<dyn TypingPatternGenerator>, GeneratorError> {
+    let header = Arc::new(ListOfPatternsGenerator::new(
+        "if_header",
+        vec![
+            Arc::new(SingleStringGenerator::new("if", "if (")),
+            condition,
+            Arc::new(SingleStringGenerator::new("if_close_paren", ")")),
+        ],
+        HashMap::from([("delimiter", String::from(""))]),
+    )?);
+    Ok(Arc::new(BlockGenerator::new(
+        name,
+        header,
+        statement,
+        HashMap::from([
+            ("min_lines", min_lines.to_string()),
+            ("max_lines", max_lines.to_string()),
+        ]),
+    )?))
+}
+
+/// Creates a Rust `match scrutinee {` block with `arm_pattern => arm_value,` lines.
+pub fn create_match_generator(
+    name: &'static str,
+    scrutinee: Arc<dyn TypingPatternGenerator>,
+    arm_pattern: Arc<dyn TypingPatternGenerator>,
+    arm_value: Arc<dyn TypingPatternGenerator>,
+    min_arms: u32,
+    max_arms: u32,
+) -> Result<Arc<dyn TypingPatternGenerator>, GeneratorError> {
+    let header = Arc::new(ListOfPatternsGenerator::new(
+        "match_header",
+        vec![
+            Arc::new(SingleStringGenerator::new("match", "match")),
+            scrutinee,
+        ],
+        HashMap::new(),
+    )?);
+    let arm = Arc::new(ListOfPatternsGenerator::new(
+        "match_arm",
+        vec![
+            arm_pattern,
+            Arc::new(SingleStringGenerator::new("fat_arrow", " => ")),
+            arm_value,
+            Arc::new(SingleStringGenerator::new("comma", ",")),
+        ],
+        HashMap::from([("delimiter", String::from(""))]),
+    )?);
+    Ok(Arc::new(BlockGenerator::new(
+        name,
+        header,
+        arm,
+        HashMap::from([
+            ("min_lines", min_arms.to_string()),
+            ("max_lines", max_arms.to_string()),
+        ]),
+    )?))
+}
+
+/// Builds the default drill tree, registering the generators it is meant to use.
+pub fn create_tree_graph() -> Result<GeneratorGraph, GeneratorError> {
+    let coding_generator = create_coding_generators()?;
+    let number_arguments = Arc::new(RepeatPatternGenerator::new(
+        "number_arguments",
+        coding_generator.number_list.clone(),
+        HashMap::from([("count", "3".to_string()), ("delimiter", ", ".to_string())]),
+    )?);
+    let method_call_generator = create_method_call_generator(
+        "method_call",
+        coding_generator.symbols.clone(),
+        coding_generator.open_paren.clone(),
+        coding_generator.close_paren.clone(),
+        number_arguments.clone(),
+        ", ".to_string(),
+        0,
+        2,
+    )?;
+
+    let expression = Arc::new(ExpressionGenerator::new(
+        "expression",
+        vec![
+            coding_generator.number.clone(),
+            coding_generator.array_deref.clone(),
+            method_call_generator.clone(),
+        ],
+        None,
+        HashMap::from([("max_depth", "2".to_string())]),
+    )?);
+
+    let condition = Arc::new(ExpressionGenerator::new(
+        "condition",
+        vec![
+            coding_generator.number.clone(),
+            coding_generator.array_deref.clone(),
+            coding_generator.symbols.clone(),
+        ],
+        None,
+        HashMap::from([
+            ("groups", "comparison,logical".to_string()),
+            ("max_depth", "2".to_string()),
+        ]),
+    )?);
+    let statement = Arc::new(ListOfPatternsGenerator::new(
+        "statement",
+        vec![
+            method_call_generator.clone(),
+            coding_generator.semicolon.clone(),
+        ],
+        HashMap::from([("delimiter", "".to_string())]),
+    )?);
+    let if_block = create_if_block_generator("if_block", condition, statement, 1, 3)?;
+    let match_block = create_match_generator(
+        "match_block",
+        coding_generator.symbols.clone(),
+        coding_generator.number.clone(),
+        method_call_generator.clone(),
+        2,
+        4,
+    )?;
+
+    let tree_content = Arc::new(RwLock::new(WeightedPatternGenerator::new(
+        "tree_content",
+        vec![
+            (1f32, coding_generator.number.clone()),
+            (1f32, coding_generator.array_deref.clone()),
+            (1f32, method_call_generator.clone()),
+            (1f32, expression.clone()),
+        ],
+    )?));
+
+    let repeated_subtrees = Arc::new(RandomRepeatGenerator::new(
+        "repeated_subtrees",
+        tree_content.clone(),
+        HashMap::from([
+            ("delimiter", ", ".to_string()),
+            ("min_count", "1".to_string()),
+            ("max_count", "3".to_string()),
+        ]),
+    )?);
+
+    let value = Box::new(ListOfPatternsGenerator::new(
+        "tree",
+        vec![
+            coding_generator.open_bracket.clone(),
+            repeated_subtrees.clone(),
+            coding_generator.close_bracket.clone(),
+        ],
+        HashMap::from([("delimiter", "".to_string())]),
+    )?);
+    let tree_generator = Arc::new(RwLock::new(value));
+    // tree_content.write().unwrap().patterns[1] = (5f32, repeated_subtrees.clone());
+
+    Ok(GeneratorGraph::new(
+        tree_generator,
+        vec![
+            coding_generator.number,
+            coding_generator.array_deref,
+            coding_generator.string_literal,
+            coding_generator.balanced_brackets,
+            coding_generator.closing_run,
+            method_call_generator,
+            expression,
+            if_block,
+            match_block,
+            tree_content,
+            repeated_subtrees,
+        ],
+    ))
 }
@@ -0,0 +1,97 @@
+use crate::generators::helpers::uppercase_first_letter;
+use crate::generators::TypingPatternGenerator;
+use std::sync::Arc;
+
+/// A lazy list of patterns, as returned by `TypingPatternGenerator::enumerate`.
+pub type Patterns = Box<dyn Iterator<Item = String>>;
+
+/// Every combination of one pattern of each of `parts`, joined by `delimiter`, with the last part
+/// changing fastest. Parts are enumerated again each time they run out, so nothing is stored but
+/// the current combination.
+pub struct Product {
+    parts: Vec<Arc<dyn TypingPatternGenerator>>,
+    iterators: Vec<Patterns>,
+    current: Vec<String>,
+    delimiter: String,
+    camel_case_strings: bool,
+    started: bool,
+    done: bool,
+}
+
+impl Product {
+    /// Returns `None` if one of the parts can't be enumerated.
+    pub fn new(
+        parts: Vec<Arc<dyn TypingPatternGenerator>>,
+        delimiter: &str,
+        camel_case_strings: bool,
+    ) -> Option<Self> {
+        let iterators = parts
+            .iter()
+            .map(|part| part.enumerate())
+            .collect::<Option<Vec<Patterns>>>()?;
+        Some(Product {
+            parts,
+            iterators,
+            current: Vec::new(),
+            delimiter: delimiter.to_string(),
+            camel_case_strings,
+            started: false,
+            done: false,
+        })
+    }
+
+    /// Moves to the next combination, returning false when there is none.
+    fn advance(&mut self) -> bool {
+        if !self.started {
+            self.started = true;
+            for iterator in &mut self.iterators {
+                match iterator.next() {
+                    Some(pattern) => self.current.push(pattern),
+                    None => return false,
+                }
+            }
+            return true;
+        }
+        for i in (0..self.parts.len()).rev() {
+            if let Some(pattern) = self.iterators[i].next() {
+                self.current[i] = pattern;
+                return true;
+            }
+            // Start this part over and carry on to the one before it.
+            self.iterators[i] = self.parts[i].enumerate().unwrap();
+            self.current[i] = self.iterators[i].next().unwrap();
+        }
+        false
+    }
+}
+
+impl Iterator for Product {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        if self.done || !self.advance() {
+            self.done = true;
+            return None;
+        }
+        let parts: Vec<String> = self
+            .current
+            .iter()
+            .map(|part| {
+                if self.camel_case_strings {
+                    uppercase_first_letter(part)
+                } else {
+                    part.clone()
+                }
+            })
+            .collect();
+        Some(parts.join(&self.delimiter))
+    }
+}
+
+/// The number of ways to join `count` patterns from a generator with `cardinality` ways.
+pub fn repeat_cardinality(cardinality: Option<u128>, count: u32) -> Option<u128> {
+    if count == 0 {
+        return Some(1);
+    }
+    cardinality?.checked_pow(count)
+}
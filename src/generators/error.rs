@@ -0,0 +1,92 @@
+use std::error::Error;
+use std::fmt;
+
+/// Why a generator could not be constructed from its configuration.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeneratorError {
+    MissingOption {
+        generator: String,
+        option: String,
+    },
+    InvalidOption {
+        generator: String,
+        option: String,
+        value: String,
+        reason: String,
+    },
+    /// The range given by two options contains no values, e.g. `min_length` > `max_length`.
+    EmptyRange {
+        generator: String,
+        min_option: String,
+        max_option: String,
+        min: String,
+        max: String,
+    },
+    /// The generator has nothing to choose from, e.g. no strings or only zero weights.
+    NoChoices {
+        generator: String,
+        reason: String,
+    },
+    /// A generator graph can't be saved or restored, e.g. because it refers to a missing child.
+    InvalidGraph {
+        generator: String,
+        reason: String,
+    },
+    /// A generator script doesn't compile or fails when it runs.
+    Script {
+        generator: String,
+        reason: String,
+    },
+}
+
+impl GeneratorError {
+    pub fn generator(&self) -> &str {
+        match self {
+            GeneratorError::MissingOption { generator, .. }
+            | GeneratorError::InvalidOption { generator, .. }
+            | GeneratorError::EmptyRange { generator, .. }
+            | GeneratorError::NoChoices { generator, .. }
+            | GeneratorError::InvalidGraph { generator, .. }
+            | GeneratorError::Script { generator, .. } => generator,
+        }
+    }
+
+    /// Describes the problem without the generator's name.
+    pub fn message(&self) -> String {
+        match self {
+            GeneratorError::MissingOption { option, .. } => format!("missing option {}", option),
+            GeneratorError::InvalidOption {
+                option,
+                value,
+                reason,
+                ..
+            } => format!(
+                "invalid value {:?} for option {}: {}",
+                value, option, reason
+            ),
+            GeneratorError::EmptyRange {
+                min_option,
+                max_option,
+                min,
+                max,
+                ..
+            } => format!(
+                "empty range, {} is {} but {} is {}",
+                min_option, min, max_option, max
+            ),
+            GeneratorError::NoChoices { reason, .. } => {
+                format!("nothing to choose from, {}", reason)
+            }
+            GeneratorError::InvalidGraph { reason, .. } => format!("invalid graph, {}", reason),
+            GeneratorError::Script { reason, .. } => format!("script error, {}", reason),
+        }
+    }
+}
+
+impl fmt::Display for GeneratorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.generator(), self.message())
+    }
+}
+
+impl Error for GeneratorError {}
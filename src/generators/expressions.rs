@@ -0,0 +1,407 @@
+use crate::generators::error::GeneratorError;
+use crate::generators::helpers::{check_options, check_range, config_range, config_value};
+use crate::generators::random::{self, GeneratorRng};
+use crate::generators::snapshot::{GeneratorSnapshot, OperatorSnapshot};
+use crate::generators::{TypingPattern, TypingPatternGenerator};
+use rand::prelude::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Arity {
+    Unary,
+    Binary,
+    /// `cond ? then : else`
+    Ternary,
+    /// `value?.member`
+    OptionalChain,
+}
+
+/// An operator with JavaScript-like precedence, where higher binds tighter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Operator {
+    pub symbol: &'static str,
+    pub arity: Arity,
+    pub precedence: u8,
+    pub right_associative: bool,
+}
+
+const fn binary(symbol: &'static str, precedence: u8) -> Operator {
+    Operator {
+        symbol,
+        arity: Arity::Binary,
+        precedence,
+        right_associative: false,
+    }
+}
+
+const fn unary(symbol: &'static str) -> Operator {
+    Operator {
+        symbol,
+        arity: Arity::Unary,
+        precedence: 13,
+        right_associative: true,
+    }
+}
+
+const TERNARY: Operator = Operator {
+    symbol: "?:",
+    arity: Arity::Ternary,
+    precedence: 1,
+    right_associative: true,
+};
+const OPTIONAL_CHAIN: Operator = Operator {
+    symbol: "?.",
+    arity: Arity::OptionalChain,
+    precedence: 14,
+    right_associative: false,
+};
+
+pub const OPERATOR_GROUPS: [&str; 7] = [
+    "arithmetic",
+    "comparison",
+    "logical",
+    "bitwise",
+    "ternary",
+    "nullish",
+    "optional_chaining",
+];
+
+/// Looks up an operator of any group by its symbol and arity.
+pub fn find_operator(symbol: &str, arity: Arity) -> Option<Operator> {
+    OPERATOR_GROUPS
+        .iter()
+        .flat_map(|group| operator_group(group).unwrap())
+        .find(|o| o.symbol == symbol && o.arity == arity)
+}
+
+/// Returns the operators of a named group.
+pub fn operator_group(group: &str) -> Option<Vec<Operator>> {
+    let operators = match group {
+        "arithmetic" => vec![
+            binary("+", 11),
+            binary("-", 11),
+            binary("*", 12),
+            binary("/", 12),
+            binary("%", 12),
+            unary("-"),
+        ],
+        "comparison" => vec![
+            binary("==", 8),
+            binary("!=", 8),
+            binary("<", 9),
+            binary("<=", 9),
+            binary(">", 9),
+            binary(">=", 9),
+        ],
+        "logical" => vec![binary("||", 3), binary("&&", 4), unary("!")],
+        "bitwise" => vec![
+            binary("|", 5),
+            binary("^", 6),
+            binary("&", 7),
+            binary("<<", 10),
+            binary(">>", 10),
+            unary("~"),
+        ],
+        "ternary" => vec![TERNARY],
+        "nullish" => vec![binary("??", 2)],
+        "optional_chaining" => vec![OPTIONAL_CHAIN],
+        _ => return None,
+    };
+    Some(operators)
+}
+
+#[derive(Debug)]
+enum Expression {
+    Operand(String),
+    Operation(Operator, Vec<Expression>),
+}
+
+impl Expression {
+    fn precedence(&self) -> u8 {
+        match self {
+            Expression::Operand(_) => u8::MAX,
+            Expression::Operation(operator, _) => operator.precedence,
+        }
+    }
+
+    fn is_logical(&self) -> bool {
+        matches!(self, Expression::Operation(operator, _) if ["||", "&&"].contains(&operator.symbol))
+    }
+
+    fn is_nullish(&self) -> bool {
+        matches!(self, Expression::Operation(operator, _) if operator.symbol == "??")
+    }
+}
+
+/// Generates random expressions like `foo - (123 - bar[4] * baz(1, 2))` from operands produced by
+/// the `operands` generators and the comma-separated operator `groups`: `arithmetic`, `comparison`,
+/// `logical`, `bitwise`, `ternary`, `nullish` and `optional_chaining`.
+///
+/// Operators nest between `min_depth` and `max_depth` levels deep (inclusive). Parentheses are only
+/// added where precedence or associativity requires them, unless `parenthesize` is `always`.
+/// The member after `?.` comes from `members`. Without it, optional chaining is left out, as an
+/// operand like a number or a call isn't a valid member.
+#[derive(Debug)]
+pub struct ExpressionGenerator {
+    pub name: String,
+    pub operands: Vec<Arc<dyn TypingPatternGenerator>>,
+    pub members: Option<Arc<dyn TypingPatternGenerator>>,
+    pub operators: Vec<Operator>,
+    pub min_depth: u32,
+    pub max_depth: u32,
+    pub always_parenthesize: bool,
+}
+
+impl ExpressionGenerator {
+    pub fn new(
+        name: &'static str,
+        operands: Vec<Arc<dyn TypingPatternGenerator>>,
+        members: Option<Arc<dyn TypingPatternGenerator>>,
+        config: HashMap<&str, String>,
+    ) -> Result<Self, GeneratorError> {
+        check_options(
+            name,
+            &config,
+            &["groups", "parenthesize", "min_depth", "max_depth"],
+        )?;
+        let invalid = |option: &str, value: &str, reason: &str| GeneratorError::InvalidOption {
+            generator: name.to_string(),
+            option: option.to_string(),
+            value: value.to_string(),
+            reason: reason.to_string(),
+        };
+        let mut operators = Vec::new();
+        for group in config_value(name, &config, "groups", String::from("arithmetic"))?.split(',') {
+            match operator_group(group.trim()) {
+                Some(group) => operators.extend(group),
+                None => {
+                    return Err(invalid(
+                        "groups",
+                        group.trim(),
+                        "expected arithmetic, comparison, logical, bitwise, ternary, nullish or optional_chaining",
+                    ))
+                }
+            }
+        }
+        let parenthesize = config_value(name, &config, "parenthesize", String::from("minimal"))?;
+        let always_parenthesize = match parenthesize.as_str() {
+            "minimal" => false,
+            "always" => true,
+            s => return Err(invalid("parenthesize", s, "expected minimal or always")),
+        };
+        let (min_depth, max_depth) =
+            config_range(name, &config, ("min_depth", "max_depth"), (1, 3))?;
+        let generator = ExpressionGenerator {
+            name: name.to_string(),
+            operands,
+            members,
+            operators,
+            min_depth,
+            max_depth,
+            always_parenthesize,
+        };
+        generator.check()?;
+        Ok(generator)
+    }
+
+    /// The operators that can be used, leaving out `?.` if there is nothing to put after it.
+    fn usable_operators(&self) -> Vec<Operator> {
+        self.operators
+            .iter()
+            .filter(|o| o.arity != Arity::OptionalChain || self.members.is_some())
+            .copied()
+            .collect()
+    }
+
+    fn operand(&self, rng: &mut GeneratorRng) -> Expression {
+        Expression::Operand(self.operands.choose(rng).unwrap().generate().pattern)
+    }
+
+    /// Builds an expression whose operators nest exactly `depth` levels deep.
+    fn expression(&self, rng: &mut GeneratorRng, depth: u32) -> Expression {
+        if depth == 0 {
+            return self.operand(rng);
+        }
+        let operator = *self.usable_operators().choose(rng).unwrap();
+        let operand_count = match operator.arity {
+            Arity::Unary | Arity::OptionalChain => 1,
+            Arity::Binary => 2,
+            Arity::Ternary => 3,
+        };
+        // One operand carries the full depth, the others are at most as deep.
+        let deepest = rng.gen_range(0..operand_count);
+        let mut operands: Vec<Expression> = (0..operand_count)
+            .map(|i| {
+                let depth = if i == deepest {
+                    depth - 1
+                } else {
+                    rng.gen_range(0..depth)
+                };
+                self.expression(rng, depth)
+            })
+            .collect();
+        if let (Arity::OptionalChain, Some(members)) = (operator.arity, &self.members) {
+            operands.push(Expression::Operand(members.generate().pattern));
+        }
+        Expression::Operation(operator, operands)
+    }
+
+    /// Renders `operand`, the `index`th operand of `parent`, adding parentheses where needed.
+    fn render_operand(&self, parent: &Operator, index: usize, operand: &Expression) -> String {
+        let rendered = self.render(operand);
+        // `--x` or `--5` would otherwise read as the decrement operator.
+        if parent.symbol == "-" && parent.arity == Arity::Unary && rendered.starts_with('-') {
+            return format!("({})", rendered);
+        }
+        if let Expression::Operand(_) = operand {
+            return rendered;
+        }
+        let is_last = match parent.arity {
+            Arity::Unary => true,
+            Arity::Binary => index == 1,
+            Arity::Ternary => index == 2,
+            Arity::OptionalChain => false,
+        };
+        let needs_parentheses = self.always_parenthesize
+            || operand.precedence() < parent.precedence
+            // Ties bind to the side the operator associates to.
+            || (operand.precedence() == parent.precedence && parent.right_associative != is_last)
+            // JavaScript rejects `??` mixed with `||` or `&&` without parentheses.
+            || (parent.symbol == "??" && operand.is_logical())
+            || (operand.is_nullish() && ["||", "&&"].contains(&parent.symbol));
+        if needs_parentheses {
+            format!("({})", rendered)
+        } else {
+            rendered
+        }
+    }
+
+    fn render(&self, expression: &Expression) -> String {
+        match expression {
+            Expression::Operand(operand) => operand.clone(),
+            Expression::Operation(operator, operands) => {
+                let rendered: Vec<String> = operands
+                    .iter()
+                    .enumerate()
+                    .map(|(i, operand)| self.render_operand(operator, i, operand))
+                    .collect();
+                match operator.arity {
+                    Arity::Unary => format!("{}{}", operator.symbol, rendered[0]),
+                    Arity::Binary => {
+                        format!("{} {} {}", rendered[0], operator.symbol, rendered[1])
+                    }
+                    Arity::Ternary => {
+                        format!("{} ? {} : {}", rendered[0], rendered[1], rendered[2])
+                    }
+                    // The member is an operand and never needs parentheses.
+                    Arity::OptionalChain => format!("{}?.{}", rendered[0], rendered[1]),
+                }
+            }
+        }
+    }
+}
+
+impl TypingPatternGenerator for ExpressionGenerator {
+    fn generate(&self) -> TypingPattern {
+        let mut rng = random::rng();
+        let depth = rng.gen_range(self.min_depth..=self.max_depth);
+        let expression = self.expression(&mut rng, depth);
+
+        TypingPattern {
+            name: self.name.clone(),
+            pattern: self.render(&expression),
+            children: Vec::new(),
+        }
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn snapshot(&self, children: &[usize]) -> Option<GeneratorSnapshot> {
+        Some(GeneratorSnapshot::Expression {
+            name: self.name.clone(),
+            operands: children[..self.operands.len()].to_vec(),
+            members: children.get(self.operands.len()).copied(),
+            operators: self
+                .operators
+                .iter()
+                .map(|o| OperatorSnapshot {
+                    symbol: o.symbol.to_string(),
+                    arity: o.arity,
+                })
+                .collect(),
+            min_depth: self.min_depth,
+            max_depth: self.max_depth,
+            always_parenthesize: self.always_parenthesize,
+        })
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        vec![
+            (
+                "operators",
+                self.operators
+                    .iter()
+                    .map(|o| o.symbol)
+                    .collect::<Vec<&str>>()
+                    .join(" "),
+            ),
+            ("min_depth", self.min_depth.to_string()),
+            ("max_depth", self.max_depth.to_string()),
+            (
+                "parenthesize",
+                String::from(if self.always_parenthesize {
+                    "always"
+                } else {
+                    "minimal"
+                }),
+            ),
+        ]
+    }
+
+    fn children(&self) -> Vec<Arc<dyn TypingPatternGenerator>> {
+        let mut children = self.operands.clone();
+        children.extend(self.members.clone());
+        children
+    }
+
+    fn min_length(&self, children: &[usize]) -> usize {
+        let operand = children[..self.operands.len()]
+            .iter()
+            .copied()
+            .min()
+            .unwrap_or(0);
+        // Every operator adds its symbol.
+        if self.min_depth == 0 {
+            operand
+        } else {
+            operand.max(1)
+        }
+    }
+
+    fn check(&self) -> Result<(), GeneratorError> {
+        if self.operands.is_empty() {
+            return Err(GeneratorError::NoChoices {
+                generator: self.name.clone(),
+                reason: String::from("there are no operands"),
+            });
+        }
+        if self.usable_operators().is_empty() {
+            return Err(GeneratorError::NoChoices {
+                generator: self.name.clone(),
+                reason: String::from("there are no operators, optional chaining needs members"),
+            });
+        }
+        check_range(
+            &self.name,
+            ("min_depth", "max_depth"),
+            &self.min_depth,
+            &self.max_depth,
+        )
+    }
+}
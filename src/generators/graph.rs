@@ -0,0 +1,521 @@
+use crate::generators::enumeration::Patterns;
+use crate::generators::error::GeneratorError;
+use crate::generators::snapshot::{GraphSnapshot, SNAPSHOT_VERSION};
+use crate::generators::TypingPatternGenerator;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+use std::{fmt, fs, io};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// A problem found in a generator graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lint {
+    pub severity: Severity,
+    pub generator: String,
+    pub message: String,
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{}: {}: {}", severity, self.generator, self.message)
+    }
+}
+
+/// Identifies a shared generator by the address it points to.
+fn node_id(generator: &Arc<dyn TypingPatternGenerator>) -> *const () {
+    Arc::as_ptr(generator) as *const ()
+}
+
+/// A generator tree, or rather graph as children are shared, reached from `root`.
+///
+/// `named` lists generators that are meant to be used, so the ones `root` never reaches
+/// can be reported.
+#[derive(Debug)]
+pub struct GeneratorGraph {
+    pub root: Arc<dyn TypingPatternGenerator>,
+    pub named: Vec<Arc<dyn TypingPatternGenerator>>,
+}
+
+impl GeneratorGraph {
+    pub fn new(
+        root: Arc<dyn TypingPatternGenerator>,
+        named: Vec<Arc<dyn TypingPatternGenerator>>,
+    ) -> Self {
+        GeneratorGraph { root, named }
+    }
+
+    /// All generators reachable from the root, each once, in depth-first order.
+    pub fn nodes(&self) -> Vec<Arc<dyn TypingPatternGenerator>> {
+        let mut nodes = Vec::new();
+        let mut seen = HashSet::new();
+        let mut stack = vec![self.root.clone()];
+        while let Some(node) = stack.pop() {
+            if !seen.insert(node_id(&node)) {
+                continue;
+            }
+            stack.extend(node.children().into_iter().rev());
+            nodes.push(node);
+        }
+        nodes
+    }
+
+    /// The first generator called `name`, reachable from the root or named.
+    pub fn find(&self, name: &str) -> Option<Arc<dyn TypingPatternGenerator>> {
+        self.nodes()
+            .into_iter()
+            .chain(self.named.iter().cloned())
+            .find(|node| node.name() == name)
+    }
+
+    /// Checks every generator's settings and looks for unreachable generators, cycles, output
+    /// that can be empty and names used by several generators. Errors come first.
+    pub fn check(&self) -> Vec<Lint> {
+        let nodes = self.nodes();
+        let index: HashMap<*const (), usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node_id(node), i))
+            .collect();
+        let children: Vec<Vec<usize>> = nodes
+            .iter()
+            .map(|node| node.children().iter().map(|c| index[&node_id(c)]).collect())
+            .collect();
+
+        let mut lints = Vec::new();
+        for node in &nodes {
+            if let Err(e) = node.check() {
+                lints.push(Lint {
+                    severity: Severity::Error,
+                    generator: e.generator().to_string(),
+                    message: e.message(),
+                });
+            }
+            for warning in node.warnings() {
+                lints.push(Lint {
+                    severity: Severity::Warning,
+                    generator: node.name(),
+                    message: warning,
+                });
+            }
+        }
+
+        let min_lengths = min_lengths(&nodes, &children);
+        for (i, node) in nodes.iter().enumerate() {
+            let lint = |severity, message: &str| Lint {
+                severity,
+                generator: node.name(),
+                message: message.to_string(),
+            };
+            if min_lengths[i] == usize::MAX {
+                // Only report where the recursion is, not every generator above it.
+                if children[i]
+                    .iter()
+                    .all(|&c| !is_cyclic_from(c, i, &children))
+                {
+                    continue;
+                }
+                lints.push(lint(
+                    Severity::Error,
+                    "never finishes, every choice leads back into a cycle",
+                ));
+            } else if min_lengths[i] == 0 && children[i].iter().all(|&c| min_lengths[c] > 0) {
+                lints.push(lint(Severity::Warning, "output can be empty"));
+            }
+        }
+
+        for cycle in find_cycles(&children) {
+            let path: Vec<String> = cycle
+                .iter()
+                .chain(cycle.first())
+                .map(|&i| nodes[i].name())
+                .collect();
+            lints.push(Lint {
+                severity: Severity::Warning,
+                generator: nodes[cycle[0]].name(),
+                message: format!(
+                    "cycle {} has no depth limit, so patterns can grow without bounds",
+                    path.join(" -> ")
+                ),
+            });
+        }
+
+        let mut unreachable = Vec::new();
+        for named in &self.named {
+            if !index.contains_key(&node_id(named)) {
+                lints.push(Lint {
+                    severity: Severity::Warning,
+                    generator: named.name(),
+                    message: format!("is not reachable from {}", self.root.name()),
+                });
+                unreachable.push(named.clone());
+            }
+        }
+
+        let mut counts: Vec<(String, usize)> = Vec::new();
+        for node in nodes.iter().chain(&unreachable) {
+            let name = node.name();
+            match counts.iter_mut().find(|(n, _)| *n == name) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((name, 1)),
+            }
+        }
+        for (name, count) in counts {
+            if count > 1 {
+                lints.push(Lint {
+                    severity: Severity::Warning,
+                    generator: name,
+                    message: format!(
+                        "is the name of {} generators, looking it up finds only the first",
+                        count
+                    ),
+                });
+            }
+        }
+
+        lints.sort_by_key(|lint| std::cmp::Reverse(lint.severity));
+        lints
+    }
+
+    /// The cardinality of every node, in the order of `nodes()`. Generators in a cycle, and the
+    /// ones using them, have no limit.
+    pub fn cardinalities(&self) -> Vec<Option<u128>> {
+        let nodes = self.nodes();
+        let index: HashMap<*const (), usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node_id(node), i))
+            .collect();
+        let children: Vec<Vec<usize>> = nodes
+            .iter()
+            .map(|node| node.children().iter().map(|c| index[&node_id(c)]).collect())
+            .collect();
+        let in_cycle: Vec<bool> = (0..nodes.len())
+            .map(|i| children[i].iter().any(|&c| is_cyclic_from(c, i, &children)))
+            .collect();
+
+        let mut cardinalities = vec![None; nodes.len()];
+        for i in 0..nodes.len() {
+            cardinality_of(i, &nodes, &children, &in_cycle, &mut cardinalities);
+        }
+        cardinalities.into_iter().map(Option::flatten).collect()
+    }
+
+    /// The number of ways the root can generate a pattern, see `TypingPatternGenerator::cardinality`.
+    pub fn cardinality(&self) -> Option<u128> {
+        self.cardinalities()[0]
+    }
+
+    /// Lists the patterns of the root lazily, if there is a limited number of them.
+    pub fn enumerate(&self) -> Option<Patterns> {
+        self.cardinality()?;
+        self.root.enumerate()
+    }
+
+    /// The graph in serializable form. Generators reached from several places, or named and
+    /// reached, are stored once.
+    ///
+    /// Fails for graphs with cycles, which `from_snapshot` can't rebuild, and lists every
+    /// generator that has no serializable form.
+    pub fn snapshot(&self) -> Result<GraphSnapshot, GeneratorError> {
+        let mut nodes: Vec<Arc<dyn TypingPatternGenerator>> = Vec::new();
+        let mut index = HashMap::new();
+        let mut stack: Vec<_> = std::iter::once(&self.root)
+            .chain(&self.named)
+            .rev()
+            .cloned()
+            .collect();
+        while let Some(node) = stack.pop() {
+            if index.contains_key(&node_id(&node)) {
+                continue;
+            }
+            index.insert(node_id(&node), nodes.len());
+            stack.extend(node.children().into_iter().rev());
+            nodes.push(node);
+        }
+
+        let children: Vec<Vec<usize>> = nodes
+            .iter()
+            .map(|node| node.children().iter().map(|c| index[&node_id(c)]).collect())
+            .collect();
+        if let Some(cycle) = find_cycles(&children).first() {
+            let path: Vec<String> = cycle
+                .iter()
+                .chain(cycle.first())
+                .map(|&i| nodes[i].name())
+                .collect();
+            return Err(GeneratorError::InvalidGraph {
+                generator: nodes[cycle[0]].name(),
+                reason: format!(
+                    "the cycle {} can't be saved, snapshots with cycles can't be loaded",
+                    path.join(" -> ")
+                ),
+            });
+        }
+
+        let mut generators = Vec::new();
+        let mut unsupported = Vec::new();
+        for (node, children) in nodes.iter().zip(&children) {
+            match node.snapshot(children) {
+                Some(snapshot) => generators.push(snapshot),
+                None => unsupported.push(node),
+            }
+        }
+        if let Some(first) = unsupported.first() {
+            let names: Vec<String> = unsupported
+                .iter()
+                .map(|node| format!("{} ({})", node.name(), node.type_name()))
+                .collect();
+            return Err(GeneratorError::InvalidGraph {
+                generator: first.name(),
+                reason: format!("can't serialize {}", names.join(", ")),
+            });
+        }
+        Ok(GraphSnapshot {
+            version: SNAPSHOT_VERSION,
+            root: index[&node_id(&self.root)],
+            named: self.named.iter().map(|n| index[&node_id(n)]).collect(),
+            generators,
+        })
+    }
+
+    /// Rebuilds a graph from a snapshot, checking every generator on the way. Generators that
+    /// appear once in the snapshot are shared again.
+    pub fn from_snapshot(snapshot: &GraphSnapshot) -> Result<Self, GeneratorError> {
+        let invalid = |reason: String| GeneratorError::InvalidGraph {
+            generator: String::from("snapshot"),
+            reason,
+        };
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(invalid(format!(
+                "version {} is not supported, expected {}",
+                snapshot.version, SNAPSHOT_VERSION
+            )));
+        }
+        let count = snapshot.generators.len();
+        for i in std::iter::once(snapshot.root)
+            .chain(snapshot.named.iter().copied())
+            .chain(snapshot.generators.iter().flat_map(|g| g.children()))
+        {
+            if i >= count {
+                return Err(invalid(format!(
+                    "generator {} doesn't exist, there are {}",
+                    i, count
+                )));
+            }
+        }
+
+        let mut built = vec![None; count];
+        let mut building = vec![false; count];
+        let root = build_node(snapshot, snapshot.root, &mut built, &mut building)?;
+        let named = snapshot
+            .named
+            .iter()
+            .map(|&i| build_node(snapshot, i, &mut built, &mut building))
+            .collect::<Result<Vec<_>, GeneratorError>>()?;
+        Ok(GeneratorGraph::new(root, named))
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let snapshot: GraphSnapshot = serde_json::from_str(&fs::read_to_string(path)?)?;
+        GeneratorGraph::from_snapshot(&snapshot)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let snapshot = self
+            .snapshot()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(path, serde_json::to_string_pretty(&snapshot)?)
+    }
+
+    /// Renders the graph in Graphviz's DOT language, one box per generator.
+    pub fn to_dot(&self) -> String {
+        let nodes = self.nodes();
+        let index: HashMap<*const (), usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node_id(node), i))
+            .collect();
+        let mut dot = String::from("digraph generators {\n    node [shape=box];\n");
+        for (i, node) in nodes.iter().enumerate() {
+            let mut label = vec![node.name(), node.type_name().to_string()];
+            label.extend(
+                node.parameters()
+                    .into_iter()
+                    .map(|(name, value)| format!("{} = {}", name, value)),
+            );
+            let label: Vec<String> = label.iter().map(|line| escape_dot(line)).collect();
+            dot.push_str(&format!("    n{} [label=\"{}\"];\n", i, label.join("\\n")));
+        }
+        for (i, node) in nodes.iter().enumerate() {
+            for child in node.children() {
+                dot.push_str(&format!("    n{} -> n{};\n", i, index[&node_id(&child)]));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Renders the graph as an indented outline. Generators that appear more than once are
+    /// only expanded the first time.
+    pub fn outline(&self) -> String {
+        let mut outline = String::new();
+        let mut seen = HashSet::new();
+        outline_node(&self.root, 0, &mut seen, &mut outline);
+        outline
+    }
+}
+
+/// Builds generator `i` of a snapshot after its children, remembering the results so shared
+/// generators are built once.
+fn build_node(
+    snapshot: &GraphSnapshot,
+    i: usize,
+    built: &mut Vec<Option<Arc<dyn TypingPatternGenerator>>>,
+    building: &mut Vec<bool>,
+) -> Result<Arc<dyn TypingPatternGenerator>, GeneratorError> {
+    if let Some(generator) = &built[i] {
+        return Ok(generator.clone());
+    }
+    let generator = &snapshot.generators[i];
+    if std::mem::replace(&mut building[i], true) {
+        return Err(GeneratorError::InvalidGraph {
+            generator: generator.name(),
+            reason: String::from("snapshots with cycles can't be loaded"),
+        });
+    }
+    let children = generator
+        .children()
+        .into_iter()
+        .map(|c| build_node(snapshot, c, built, building))
+        .collect::<Result<Vec<_>, GeneratorError>>()?;
+    let node = generator.build(children)?;
+    built[i] = Some(node.clone());
+    Ok(node)
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn outline_node(
+    node: &Arc<dyn TypingPatternGenerator>,
+    depth: usize,
+    seen: &mut HashSet<*const ()>,
+    outline: &mut String,
+) {
+    outline.push_str(&"  ".repeat(depth));
+    outline.push_str(&node.name());
+    if !seen.insert(node_id(node)) {
+        outline.push_str(" (see above)\n");
+        return;
+    }
+    outline.push_str(&format!(" ({})", node.type_name()));
+    for (name, value) in node.parameters() {
+        outline.push_str(&format!(" {}={}", name, value));
+    }
+    outline.push('\n');
+    for child in node.children() {
+        outline_node(&child, depth + 1, seen, outline);
+    }
+}
+
+/// Lower bounds for the output length of every node, found by iterating from "never finishes"
+/// until nothing changes, which resolves cycles.
+fn min_lengths(nodes: &[Arc<dyn TypingPatternGenerator>], children: &[Vec<usize>]) -> Vec<usize> {
+    let mut lengths = vec![usize::MAX; nodes.len()];
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (i, node) in nodes.iter().enumerate() {
+            let child_lengths: Vec<usize> = children[i].iter().map(|&c| lengths[c]).collect();
+            let length = node.min_length(&child_lengths);
+            if length < lengths[i] {
+                lengths[i] = length;
+                changed = true;
+            }
+        }
+    }
+    lengths
+}
+
+/// Computes the cardinality of node `i` after the ones of its children, remembering the results.
+fn cardinality_of(
+    i: usize,
+    nodes: &[Arc<dyn TypingPatternGenerator>],
+    children: &[Vec<usize>],
+    in_cycle: &[bool],
+    cardinalities: &mut Vec<Option<Option<u128>>>,
+) -> Option<u128> {
+    if in_cycle[i] {
+        return None;
+    }
+    if let Some(cardinality) = cardinalities[i] {
+        return cardinality;
+    }
+    let child_cardinalities: Vec<Option<u128>> = children[i]
+        .iter()
+        .map(|&c| cardinality_of(c, nodes, children, in_cycle, cardinalities))
+        .collect();
+    let cardinality = nodes[i].cardinality(&child_cardinalities);
+    cardinalities[i] = Some(cardinality);
+    cardinality
+}
+
+/// Whether `target` can be reached from `start`.
+fn is_cyclic_from(start: usize, target: usize, children: &[Vec<usize>]) -> bool {
+    let mut seen = vec![false; children.len()];
+    let mut stack = vec![start];
+    while let Some(i) = stack.pop() {
+        if i == target {
+            return true;
+        }
+        if !std::mem::replace(&mut seen[i], true) {
+            stack.extend(&children[i]);
+        }
+    }
+    false
+}
+
+/// One cycle per back edge found by a depth-first search from each node not visited yet.
+fn find_cycles(children: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    fn visit(
+        i: usize,
+        children: &[Vec<usize>],
+        path: &mut Vec<usize>,
+        done: &mut [bool],
+        cycles: &mut Vec<Vec<usize>>,
+    ) {
+        path.push(i);
+        for &c in &children[i] {
+            if let Some(start) = path.iter().position(|&p| p == c) {
+                cycles.push(path[start..].to_vec());
+            } else if !done[c] {
+                visit(c, children, path, done, cycles);
+            }
+        }
+        path.pop();
+        done[i] = true;
+    }
+
+    let mut cycles = Vec::new();
+    let mut done = vec![false; children.len()];
+    for i in 0..children.len() {
+        if !done[i] {
+            visit(i, children, &mut Vec::new(), &mut done, &mut cycles);
+        }
+    }
+    cycles
+}
@@ -1,3 +1,11 @@
+use crate::generators::error::GeneratorError;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
 // From: https://stackoverflow.com/questions/38406793/why-is-capitalizing-the-first-letter-of-a-string-so-convoluted-in-rust
 pub fn uppercase_first_letter(s: &str) -> String {
     let mut c = s.chars();
@@ -6,3 +14,153 @@ pub fn uppercase_first_letter(s: &str) -> String {
         Some(f) => f.to_uppercase().collect::<String>() + c.as_str(),
     }
 }
+
+/// Uppercases the first letter of `s[start..]` in place.
+pub fn uppercase_first_letter_from(s: &mut String, start: usize) {
+    if let Some(f) = s[start..].chars().next() {
+        if !f.is_uppercase() {
+            s.replace_range(start..start + f.len_utf8(), &f.to_uppercase().to_string());
+        }
+    }
+}
+
+/// Reads a word list with one word per line, skipping blank lines.
+pub fn load_word_list<P: AsRef<Path>>(path: P) -> io::Result<Vec<String>> {
+    Ok(fs::read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+/// Rejects options the generator does not know, which usually are typos.
+pub fn check_options(
+    generator: &str,
+    config: &HashMap<&str, String>,
+    known: &[&str],
+) -> Result<(), GeneratorError> {
+    let mut unknown: Vec<&&str> = config.keys().filter(|k| !known.contains(k)).collect();
+    unknown.sort();
+    match unknown.first() {
+        Some(option) => Err(GeneratorError::InvalidOption {
+            generator: generator.to_string(),
+            option: option.to_string(),
+            value: config[**option].clone(),
+            reason: format!("unknown option, expected one of {}", known.join(", ")),
+        }),
+        None => Ok(()),
+    }
+}
+
+fn parse_option<T: FromStr>(generator: &str, key: &str, value: &str) -> Result<T, GeneratorError>
+where
+    T::Err: Display,
+{
+    value
+        .parse()
+        .map_err(|e: T::Err| GeneratorError::InvalidOption {
+            generator: generator.to_string(),
+            option: key.to_string(),
+            value: value.to_string(),
+            reason: e.to_string(),
+        })
+}
+
+/// Parses the config option `key`, falling back to `default` when it is not set.
+pub fn config_value<T: FromStr>(
+    generator: &str,
+    config: &HashMap<&str, String>,
+    key: &str,
+    default: T,
+) -> Result<T, GeneratorError>
+where
+    T::Err: Display,
+{
+    match config.get(key) {
+        Some(s) => parse_option(generator, key, s),
+        None => Ok(default),
+    }
+}
+
+/// Parses the config option `key`, which has no default.
+pub fn required_config_value<T: FromStr>(
+    generator: &str,
+    config: &HashMap<&str, String>,
+    key: &str,
+) -> Result<T, GeneratorError>
+where
+    T::Err: Display,
+{
+    match config.get(key) {
+        Some(s) => parse_option(generator, key, s),
+        None => Err(GeneratorError::MissingOption {
+            generator: generator.to_string(),
+            option: key.to_string(),
+        }),
+    }
+}
+
+/// Parses a probability between 0 and 1.
+pub fn config_probability(
+    generator: &str,
+    config: &HashMap<&str, String>,
+    key: &str,
+    default: f64,
+) -> Result<f64, GeneratorError> {
+    let probability = config_value(generator, config, key, default)?;
+    check_probability(generator, key, probability)?;
+    Ok(probability)
+}
+
+/// Checks that the option `key` is a probability between 0 and 1.
+pub fn check_probability(
+    generator: &str,
+    key: &str,
+    probability: f64,
+) -> Result<(), GeneratorError> {
+    if !(0.0..=1.0).contains(&probability) {
+        return Err(GeneratorError::InvalidOption {
+            generator: generator.to_string(),
+            option: key.to_string(),
+            value: probability.to_string(),
+            reason: String::from("probabilities are between 0 and 1"),
+        });
+    }
+    Ok(())
+}
+
+/// Parses the inclusive range given by the options `min_key` and `max_key`.
+pub fn config_range<T: FromStr + PartialOrd + Display>(
+    generator: &str,
+    config: &HashMap<&str, String>,
+    (min_key, max_key): (&str, &str),
+    (default_min, default_max): (T, T),
+) -> Result<(T, T), GeneratorError>
+where
+    T::Err: Display,
+{
+    let min = config_value(generator, config, min_key, default_min)?;
+    let max = config_value(generator, config, max_key, default_max)?;
+    check_range(generator, (min_key, max_key), &min, &max)?;
+    Ok((min, max))
+}
+
+/// Checks that the inclusive range `min..=max` is not empty.
+pub fn check_range<T: PartialOrd + Display>(
+    generator: &str,
+    (min_key, max_key): (&str, &str),
+    min: &T,
+    max: &T,
+) -> Result<(), GeneratorError> {
+    if min > max {
+        return Err(GeneratorError::EmptyRange {
+            generator: generator.to_string(),
+            min_option: min_key.to_string(),
+            max_option: max_key.to_string(),
+            min: min.to_string(),
+            max: max.to_string(),
+        });
+    }
+    Ok(())
+}
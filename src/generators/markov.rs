@@ -0,0 +1,266 @@
+use crate::generators::error::GeneratorError;
+use crate::generators::helpers::{check_options, check_range, config_range};
+use crate::generators::random;
+use crate::generators::{TypingPattern, TypingPatternGenerator};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Joins the tokens of a state into a map key. Tokens never contain it.
+const STATE_SEPARATOR: &str = "\u{1f}";
+/// Marks the start (as padding) and the end (as next token) of a line.
+const BOUNDARY: &str = "";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Tokenization {
+    /// Every character is a token.
+    Chars,
+    /// Identifiers, numbers, single symbols and runs of whitespace are tokens.
+    Tokens,
+}
+
+/// An n-gram model of the lines of a corpus, mapping the last `order` tokens to counts of the next one.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MarkovModel {
+    pub order: usize,
+    pub tokenization: Tokenization,
+    pub transitions: BTreeMap<String, BTreeMap<String, u32>>,
+}
+
+impl MarkovModel {
+    pub fn new(order: usize, tokenization: Tokenization) -> Self {
+        MarkovModel {
+            order,
+            tokenization,
+            transitions: BTreeMap::new(),
+        }
+    }
+
+    /// Trains a model on every non-empty line of the given files.
+    pub fn train_from_files<P: AsRef<Path>>(
+        order: usize,
+        tokenization: Tokenization,
+        paths: &[P],
+    ) -> io::Result<Self> {
+        let mut model = MarkovModel::new(order, tokenization);
+        for path in paths {
+            model.train(&fs::read_to_string(path)?);
+        }
+        Ok(model)
+    }
+
+    pub fn train(&mut self, text: &str) {
+        for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let mut state = vec![BOUNDARY.to_string(); self.order];
+            for token in tokenize(line, self.tokenization).chain([BOUNDARY.to_string()]) {
+                *self
+                    .transitions
+                    .entry(state.join(STATE_SEPARATOR))
+                    .or_default()
+                    .entry(token.clone())
+                    .or_insert(0) += 1;
+                if self.order > 0 {
+                    state.remove(0);
+                    state.push(token);
+                }
+            }
+        }
+    }
+
+    /// Loads a model saved by `save`, rejecting one that can't be generated from.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let model: MarkovModel = serde_json::from_str(&fs::read_to_string(path)?)?;
+        model
+            .validate()
+            .map_err(|reason| io::Error::new(io::ErrorKind::InvalidData, reason))?;
+        Ok(model)
+    }
+
+    /// Checks that every state has a next token to pick.
+    pub fn validate(&self) -> Result<(), String> {
+        match self
+            .transitions
+            .iter()
+            .find(|(_, next_tokens)| next_tokens.values().all(|&count| count == 0))
+        {
+            Some((state, _)) => Err(format!(
+                "the state {:?} has no next tokens",
+                state.replace(STATE_SEPARATOR, " ")
+            )),
+            None => Ok(()),
+        }
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, serde_json::to_string(self)?)
+    }
+
+    /// Walks the chain from the start of a line until it reaches the end of a line.
+    fn generate_line(&self, rng: &mut impl Rng, max_length: usize) -> String {
+        let mut state = vec![BOUNDARY.to_string(); self.order];
+        let mut line = String::new();
+        while let Some(next_tokens) = self.transitions.get(&state.join(STATE_SEPARATOR)) {
+            let total: u64 = next_tokens.values().map(|&count| u64::from(count)).sum();
+            if total == 0 {
+                break;
+            }
+            let mut random_number = rng.gen_range(0..total);
+            let token = next_tokens
+                .iter()
+                .find(|(_, &count)| {
+                    if random_number < u64::from(count) {
+                        return true;
+                    }
+                    random_number -= u64::from(count);
+                    false
+                })
+                .map(|(token, _)| token)
+                .unwrap();
+            if token == BOUNDARY || line.chars().count() + token.chars().count() > max_length {
+                break;
+            }
+            line.push_str(token);
+            if self.order > 0 {
+                state.remove(0);
+                state.push(token.clone());
+            }
+        }
+        line
+    }
+}
+
+fn tokenize(line: &str, tokenization: Tokenization) -> Box<dyn Iterator<Item = String> + '_> {
+    match tokenization {
+        Tokenization::Chars => Box::new(line.chars().map(String::from)),
+        Tokenization::Tokens => {
+            let mut chars = line.chars().peekable();
+            Box::new(std::iter::from_fn(move || {
+                let c = chars.next()?;
+                let mut token = String::from(c);
+                let continues: fn(char) -> bool = if c.is_alphabetic() || c == '_' {
+                    |c| c.is_alphanumeric() || c == '_'
+                } else if c.is_ascii_digit() {
+                    |c| c.is_ascii_alphanumeric() || c == '.'
+                } else if c.is_whitespace() {
+                    // Runs of whitespace collapse into a single space.
+                    token = String::from(" ");
+                    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                    return Some(token);
+                } else {
+                    return Some(token);
+                };
+                while let Some(c) = chars.next_if(|c| continues(*c)) {
+                    token.push(c);
+                }
+                Some(token)
+            }))
+        }
+    }
+}
+
+/// Generates pseudo-code lines from a trained `MarkovModel`.
+///
+/// Lines shorter than `min_length` characters are extended with further lines, separated by a space.
+#[derive(Debug, Clone)]
+pub struct MarkovPatternGenerator {
+    pub name: String,
+    pub model: Arc<MarkovModel>,
+    pub min_length: usize,
+    pub max_length: usize,
+}
+
+impl MarkovPatternGenerator {
+    pub fn new(
+        name: &'static str,
+        model: Arc<MarkovModel>,
+        config: HashMap<&str, String>,
+    ) -> Result<Self, GeneratorError> {
+        check_options(name, &config, &["min_length", "max_length"])?;
+        let (min_length, max_length) =
+            config_range(name, &config, ("min_length", "max_length"), (10, 40))?;
+        Ok(MarkovPatternGenerator {
+            name: name.to_string(),
+            model,
+            min_length,
+            max_length,
+        })
+    }
+}
+
+impl TypingPatternGenerator for MarkovPatternGenerator {
+    fn generate(&self) -> TypingPattern {
+        let mut rng = random::rng();
+        let mut pattern = String::new();
+        // An empty model never produces anything, so give up after a few empty lines.
+        let mut attempts = 0;
+        while pattern.chars().count() < self.min_length && attempts < 10 {
+            let remaining = match pattern.chars().count() {
+                0 => self.max_length,
+                length => self.max_length.saturating_sub(length + 1),
+            };
+            let line = self.model.generate_line(&mut rng, remaining);
+            if line.is_empty() {
+                attempts += 1;
+                continue;
+            }
+            if !pattern.is_empty() {
+                pattern.push(' ');
+            }
+            pattern.push_str(&line);
+        }
+
+        TypingPattern {
+            name: self.name.clone(),
+            pattern,
+            children: Vec::new(),
+        }
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("order", self.model.order.to_string()),
+            ("min_length", self.min_length.to_string()),
+            ("max_length", self.max_length.to_string()),
+        ]
+    }
+
+    fn min_length(&self, _children: &[usize]) -> usize {
+        // Lines are cut short near max_length, so only a non-empty result is certain.
+        if self.model.transitions.is_empty() || self.min_length == 0 {
+            0
+        } else {
+            1
+        }
+    }
+
+    fn check(&self) -> Result<(), GeneratorError> {
+        check_range(
+            &self.name,
+            ("min_length", "max_length"),
+            &self.min_length,
+            &self.max_length,
+        )?;
+        self.model
+            .validate()
+            .map_err(|reason| GeneratorError::NoChoices {
+                generator: self.name.clone(),
+                reason,
+            })
+    }
+
+    fn with_range(&self, min: u32, max: u32) -> Option<Arc<dyn TypingPatternGenerator>> {
+        Some(Arc::new(MarkovPatternGenerator {
+            min_length: min as usize,
+            max_length: max as usize,
+            ..self.clone()
+        }))
+    }
+}
@@ -1,30 +1,240 @@
-pub(crate) mod coding;
-pub(crate) mod helpers;
-pub(crate) mod randomized;
-pub(crate) mod sequences;
-pub(crate) mod simple;
+pub mod blocks;
+pub mod coding;
+pub mod enumeration;
+pub mod error;
+pub mod expressions;
+pub mod graph;
+pub mod helpers;
+pub mod markov;
+pub mod nesting;
+pub mod preview;
+pub mod random;
+pub mod randomized;
+pub mod regex;
+pub mod script;
+pub mod sequences;
+pub mod simple;
+pub mod snapshot;
+pub mod structured;
+pub mod template;
+pub mod weak_ngrams;
 
-use std::cell::RefCell;
+use crate::generators::enumeration::Patterns;
+use crate::generators::error::GeneratorError;
+use crate::generators::snapshot::GeneratorSnapshot;
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
+use std::sync::{Arc, RwLock};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TypingPattern {
     pub name: String,
+    /// The text to type. Lines are separated by `\n` and each leading `\t` is one level of
+    /// indentation, which the session renders as tabs or spaces.
     pub pattern: String,
+    /// The patterns this one was joined from, for generators that combine their children's output.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub children: Vec<TypingPattern>,
 }
 
-pub trait TypingPatternGenerator: Debug {
+/// Generators are `Send + Sync`, so one graph can be shared between threads, e.g. to generate
+/// patterns in the background while the session reads keys.
+pub trait TypingPatternGenerator: Debug + Send + Sync {
     fn generate(&self) -> TypingPattern;
+
+    /// Appends a pattern to `pattern`, without recording the patterns it was joined from.
+    /// Generators that join their children's output override it, so a whole tree writes into
+    /// one buffer instead of allocating a `TypingPattern` per node.
+    ///
+    /// Uses the random number generator like `generate`, so both produce the same pattern for
+    /// the same seed.
+    fn generate_into(&self, pattern: &mut String) {
+        pattern.push_str(&self.generate().pattern);
+    }
+
+    fn name(&self) -> String;
+
+    /// The type of generator, e.g. `ListOfPatternsGenerator`.
+    fn type_name(&self) -> &'static str {
+        let name = std::any::type_name::<Self>();
+        name.rsplit("::").next().unwrap_or(name)
+    }
+
+    /// The settings of the generator as name and value, without its children.
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+
+    /// The generators this one draws from, in a fixed order.
+    fn children(&self) -> Vec<Arc<dyn TypingPatternGenerator>> {
+        Vec::new()
+    }
+
+    /// A lower bound on the number of chars in the generated patterns, given the bounds of
+    /// `children()` in the same order. `usize::MAX` means generating never finishes.
+    ///
+    /// The default suits generators that always output something and use all of their children.
+    fn min_length(&self, children: &[usize]) -> usize {
+        if children.contains(&usize::MAX) {
+            usize::MAX
+        } else {
+            1
+        }
+    }
+
+    /// Checks the settings again, as the public fields may have changed since construction.
+    fn check(&self) -> Result<(), GeneratorError> {
+        Ok(())
+    }
+
+    /// Describes settings that are valid but most likely a mistake, e.g. a child that is never
+    /// chosen.
+    fn warnings(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// A copy of the generator with its main range set to `min..=max`, e.g. the digits of a
+    /// number or the count of a repetition, or `None` if it has no such range. Template
+    /// placeholders like `{number:2..4}` use it. The copy isn't checked yet.
+    fn with_range(&self, _min: u32, _max: u32) -> Option<Arc<dyn TypingPatternGenerator>> {
+        None
+    }
+
+    /// The number of ways to generate a pattern, given the numbers for `children()` in the same
+    /// order, or `None` if there is no practical limit. Different ways can produce the same text,
+    /// so this is an upper bound on the number of distinct patterns.
+    fn cardinality(&self, _children: &[Option<u128>]) -> Option<u128> {
+        None
+    }
+
+    /// Lists the patterns lazily, one for each way counted by `cardinality`, or returns `None`
+    /// if the generator can't. Children are enumerated as well, so cycles recurse forever:
+    /// use `GeneratorGraph::enumerate`, which checks for them.
+    fn enumerate(&self) -> Option<Patterns> {
+        None
+    }
+
+    /// The generator in serializable form, given the indices of `children()` in the snapshot,
+    /// or `None` if it can't be serialized.
+    fn snapshot(&self, _children: &[usize]) -> Option<GeneratorSnapshot> {
+        None
+    }
 }
 
 impl<T: ?Sized + TypingPatternGenerator> TypingPatternGenerator for Box<T> {
     fn generate(&self) -> TypingPattern {
         (**self).generate()
     }
+
+    fn generate_into(&self, pattern: &mut String) {
+        (**self).generate_into(pattern)
+    }
+
+    fn name(&self) -> String {
+        (**self).name()
+    }
+
+    fn type_name(&self) -> &'static str {
+        (**self).type_name()
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        (**self).parameters()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn TypingPatternGenerator>> {
+        (**self).children()
+    }
+
+    fn min_length(&self, children: &[usize]) -> usize {
+        (**self).min_length(children)
+    }
+
+    fn check(&self) -> Result<(), GeneratorError> {
+        (**self).check()
+    }
+
+    fn warnings(&self) -> Vec<String> {
+        (**self).warnings()
+    }
+
+    fn with_range(&self, min: u32, max: u32) -> Option<Arc<dyn TypingPatternGenerator>> {
+        (**self).with_range(min, max)
+    }
+
+    fn cardinality(&self, children: &[Option<u128>]) -> Option<u128> {
+        (**self).cardinality(children)
+    }
+
+    fn enumerate(&self) -> Option<Patterns> {
+        (**self).enumerate()
+    }
+
+    fn snapshot(&self, children: &[usize]) -> Option<GeneratorSnapshot> {
+        (**self).snapshot(children)
+    }
 }
 
-impl<T: ?Sized + TypingPatternGenerator> TypingPatternGenerator for RefCell<T> {
+impl<T: ?Sized + TypingPatternGenerator> TypingPatternGenerator for RwLock<T> {
     fn generate(&self) -> TypingPattern {
-        self.borrow().generate()
+        self.read().unwrap().generate()
+    }
+
+    fn generate_into(&self, pattern: &mut String) {
+        self.read().unwrap().generate_into(pattern)
+    }
+
+    fn name(&self) -> String {
+        self.read().unwrap().name()
+    }
+
+    fn type_name(&self) -> &'static str {
+        self.read().unwrap().type_name()
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        self.read().unwrap().parameters()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn TypingPatternGenerator>> {
+        self.read().unwrap().children()
+    }
+
+    fn min_length(&self, children: &[usize]) -> usize {
+        self.read().unwrap().min_length(children)
+    }
+
+    fn check(&self) -> Result<(), GeneratorError> {
+        self.read().unwrap().check()
+    }
+
+    fn warnings(&self) -> Vec<String> {
+        self.read().unwrap().warnings()
+    }
+
+    fn with_range(&self, min: u32, max: u32) -> Option<Arc<dyn TypingPatternGenerator>> {
+        self.read().unwrap().with_range(min, max)
+    }
+
+    fn cardinality(&self, children: &[Option<u128>]) -> Option<u128> {
+        self.read().unwrap().cardinality(children)
+    }
+
+    fn enumerate(&self) -> Option<Patterns> {
+        self.read().unwrap().enumerate()
+    }
+
+    fn snapshot(&self, children: &[usize]) -> Option<GeneratorSnapshot> {
+        self.read().unwrap().snapshot(children)
+    }
+}
+
+/// The length of `count` patterns of at least `length` chars joined by `delimiter`.
+fn joined_length(count: usize, length: usize, delimiter: &str) -> usize {
+    if count == 0 {
+        return 0;
     }
+    length
+        .saturating_mul(count)
+        .saturating_add(delimiter.chars().count() * (count - 1))
 }
@@ -0,0 +1,257 @@
+use crate::generators::error::GeneratorError;
+use crate::generators::helpers::{
+    check_options, check_probability, check_range, config_probability, config_range, config_value,
+};
+use crate::generators::random::{self, GeneratorRng};
+use crate::generators::snapshot::GeneratorSnapshot;
+use crate::generators::{TypingPattern, TypingPatternGenerator};
+use rand::prelude::SliceRandom;
+use rand::Rng;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Generates correctly balanced, nested mixes of brackets such as `({[]})` or `foo([a[1]], {b})`.
+///
+/// `brackets` lists the pairs to mix, e.g. `()[]{}<>`. Brackets nest between `min_depth` and
+/// `max_depth` levels deep (inclusive), where a single pair is one level. Each pair holds up to `max_siblings`
+/// comma-separated items. With `content_probability`, items are outputs of `content` instead of
+/// empty brackets, and parens and brackets get a prefix like a call or index expression.
+///
+/// With `closing_runs` set, only the closing brackets of a nesting are generated, e.g. `)]});`,
+/// followed by `terminator` with `terminator_probability`.
+#[derive(Debug, Clone)]
+pub struct BalancedNestingGenerator {
+    pub name: String,
+    pub content: Option<Arc<dyn TypingPatternGenerator>>,
+    pub pairs: Vec<(char, char)>,
+    pub min_depth: u32,
+    pub max_depth: u32,
+    pub max_siblings: u32,
+    pub content_probability: f64,
+    pub delimiter: String,
+    pub closing_runs: bool,
+    pub terminator: String,
+    pub terminator_probability: f64,
+}
+
+impl BalancedNestingGenerator {
+    pub fn new(
+        name: &'static str,
+        content: Option<Arc<dyn TypingPatternGenerator>>,
+        config: HashMap<&str, String>,
+    ) -> Result<Self, GeneratorError> {
+        check_options(
+            name,
+            &config,
+            &[
+                "brackets",
+                "min_depth",
+                "max_depth",
+                "max_siblings",
+                "content_probability",
+                "delimiter",
+                "closing_runs",
+                "terminator",
+                "terminator_probability",
+            ],
+        )?;
+        let brackets: String = config_value(name, &config, "brackets", String::from("()[]{}"))?;
+        let chars: Vec<char> = brackets.chars().collect();
+        if chars.is_empty() || !chars.len().is_multiple_of(2) {
+            return Err(GeneratorError::InvalidOption {
+                generator: name.to_string(),
+                option: String::from("brackets"),
+                value: brackets,
+                reason: String::from("expected pairs of opening and closing brackets"),
+            });
+        }
+        let (min_depth, max_depth) =
+            config_range(name, &config, ("min_depth", "max_depth"), (1, 3))?;
+        let generator = BalancedNestingGenerator {
+            name: name.to_string(),
+            content,
+            pairs: chars.chunks(2).map(|pair| (pair[0], pair[1])).collect(),
+            min_depth,
+            max_depth,
+            max_siblings: config_value(name, &config, "max_siblings", 2)?,
+            content_probability: config_probability(name, &config, "content_probability", 0.5)?,
+            delimiter: config_value(name, &config, "delimiter", String::from(", "))?,
+            closing_runs: config_value(name, &config, "closing_runs", false)?,
+            terminator: config_value(name, &config, "terminator", String::from(";"))?,
+            terminator_probability: config_probability(
+                name,
+                &config,
+                "terminator_probability",
+                0.5,
+            )?,
+        };
+        generator.check()?;
+        Ok(generator)
+    }
+
+    fn content(&self, rng: &mut GeneratorRng) -> Option<String> {
+        match &self.content {
+            Some(content) if rng.gen_bool(self.content_probability) => {
+                Some(content.generate().pattern)
+            }
+            _ => None,
+        }
+    }
+
+    /// Generates brackets nested exactly `depth` levels deep.
+    fn nested(&self, rng: &mut GeneratorRng, depth: u32, pattern: &mut String) {
+        let (open, close) = *self.pairs.choose(rng).unwrap();
+        if open == '(' || open == '[' {
+            if let Some(prefix) = self.content(rng) {
+                pattern.push_str(&prefix);
+            }
+        }
+        pattern.push(open);
+        if depth <= 1 {
+            if let Some(content) = self.content(rng) {
+                pattern.push_str(&content);
+            }
+        } else {
+            let siblings = rng.gen_range(1..=self.max_siblings.max(1));
+            // One item carries the full depth, the others nest less or hold content.
+            let deepest = rng.gen_range(0..siblings);
+            for i in 0..siblings {
+                if i > 0 {
+                    pattern.push_str(&self.delimiter);
+                }
+                if i == deepest {
+                    self.nested(rng, depth - 1, pattern);
+                } else if let Some(content) = self.content(rng) {
+                    pattern.push_str(&content);
+                } else {
+                    let depth = rng.gen_range(1..depth);
+                    self.nested(rng, depth, pattern);
+                }
+            }
+        }
+        pattern.push(close);
+    }
+}
+
+impl TypingPatternGenerator for BalancedNestingGenerator {
+    fn generate(&self) -> TypingPattern {
+        let mut pattern = String::new();
+        self.generate_into(&mut pattern);
+        TypingPattern {
+            name: self.name.clone(),
+            pattern,
+            children: Vec::new(),
+        }
+    }
+
+    fn generate_into(&self, pattern: &mut String) {
+        let mut rng = random::rng();
+        let depth = rng.gen_range(self.min_depth..=self.max_depth);
+        if self.closing_runs {
+            for _ in 0..depth {
+                pattern.push(self.pairs.choose(&mut rng).unwrap().1);
+            }
+            if rng.gen_bool(self.terminator_probability) {
+                pattern.push_str(&self.terminator);
+            }
+        } else {
+            self.nested(&mut rng, depth, pattern);
+        }
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        vec![
+            (
+                "brackets",
+                format!(
+                    "{:?}",
+                    self.pairs
+                        .iter()
+                        .flat_map(|(open, close)| [open, close])
+                        .collect::<String>()
+                ),
+            ),
+            ("min_depth", self.min_depth.to_string()),
+            ("max_depth", self.max_depth.to_string()),
+            ("max_siblings", self.max_siblings.to_string()),
+            ("content_probability", self.content_probability.to_string()),
+            ("delimiter", format!("{:?}", self.delimiter)),
+            ("closing_runs", self.closing_runs.to_string()),
+            ("terminator", format!("{:?}", self.terminator)),
+            (
+                "terminator_probability",
+                self.terminator_probability.to_string(),
+            ),
+        ]
+    }
+
+    fn children(&self) -> Vec<Arc<dyn TypingPatternGenerator>> {
+        self.content.iter().cloned().collect()
+    }
+
+    fn snapshot(&self, children: &[usize]) -> Option<GeneratorSnapshot> {
+        Some(GeneratorSnapshot::BalancedNesting {
+            name: self.name.clone(),
+            content: children.first().copied(),
+            pairs: self.pairs.clone(),
+            min_depth: self.min_depth,
+            max_depth: self.max_depth,
+            max_siblings: self.max_siblings,
+            content_probability: self.content_probability,
+            delimiter: self.delimiter.clone(),
+            closing_runs: self.closing_runs,
+            terminator: self.terminator.clone(),
+            terminator_probability: self.terminator_probability,
+        })
+    }
+
+    fn min_length(&self, _children: &[usize]) -> usize {
+        // Content is optional, but every level has its brackets.
+        if self.closing_runs {
+            self.min_depth as usize
+        } else {
+            2 * self.min_depth as usize
+        }
+    }
+
+    fn check(&self) -> Result<(), GeneratorError> {
+        if self.pairs.is_empty() {
+            return Err(GeneratorError::NoChoices {
+                generator: self.name.clone(),
+                reason: String::from("there are no bracket pairs"),
+            });
+        }
+        if self.min_depth == 0 {
+            return Err(GeneratorError::InvalidOption {
+                generator: self.name.clone(),
+                option: String::from("min_depth"),
+                value: self.min_depth.to_string(),
+                reason: String::from("a single pair is one level, so the depth is at least 1"),
+            });
+        }
+        check_range(
+            &self.name,
+            ("min_depth", "max_depth"),
+            &self.min_depth,
+            &self.max_depth,
+        )?;
+        check_probability(&self.name, "content_probability", self.content_probability)?;
+        check_probability(
+            &self.name,
+            "terminator_probability",
+            self.terminator_probability,
+        )
+    }
+
+    fn with_range(&self, min: u32, max: u32) -> Option<Arc<dyn TypingPatternGenerator>> {
+        Some(Arc::new(BalancedNestingGenerator {
+            min_depth: min,
+            max_depth: max,
+            ..self.clone()
+        }))
+    }
+}
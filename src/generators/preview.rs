@@ -0,0 +1,174 @@
+use crate::generators::{TypingPattern, TypingPatternGenerator};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+/// Statistics over many outputs of a generator, for tuning weights and ranges before
+/// practicing with it.
+#[derive(Debug, Clone, Default)]
+pub struct Preview {
+    pub name: String,
+    pub samples: usize,
+    pub unique: usize,
+    /// The first few outputs.
+    pub examples: Vec<String>,
+    /// The length of every output in chars, sorted.
+    pub lengths: Vec<usize>,
+    pub chars: HashMap<char, usize>,
+    /// How often each path of generator names occurred, e.g. `tree / repeated_subtrees / number`.
+    /// Weighted choices return the chosen child's pattern, so they show up as the child's name.
+    pub paths: BTreeMap<Vec<String>, usize>,
+}
+
+impl Preview {
+    pub fn sample(generator: &dyn TypingPatternGenerator, samples: usize) -> Self {
+        let mut preview = Preview {
+            name: generator.name(),
+            samples,
+            ..Preview::default()
+        };
+        let mut unique = HashSet::new();
+        for _ in 0..samples {
+            let pattern = generator.generate();
+            if preview.examples.len() < 10 {
+                preview.examples.push(pattern.pattern.clone());
+            }
+            preview.lengths.push(pattern.pattern.chars().count());
+            for c in pattern.pattern.chars() {
+                *preview.chars.entry(c).or_default() += 1;
+            }
+            preview.count_paths(&pattern, &mut Vec::new());
+            unique.insert(pattern.pattern);
+        }
+        preview.unique = unique.len();
+        preview.lengths.sort_unstable();
+        preview
+    }
+
+    fn count_paths(&mut self, pattern: &TypingPattern, path: &mut Vec<String>) {
+        path.push(pattern.name.clone());
+        *self.paths.entry(path.clone()).or_default() += 1;
+        for child in &pattern.children {
+            self.count_paths(child, path);
+        }
+        path.pop();
+    }
+
+    fn mean_length(&self) -> f64 {
+        self.lengths.iter().sum::<usize>() as f64 / self.lengths.len().max(1) as f64
+    }
+
+    fn write_lengths(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (Some(&min), Some(&max)) = (self.lengths.first(), self.lengths.last()) else {
+            return Ok(());
+        };
+        writeln!(
+            f,
+            "Length: min {}, median {}, mean {:.1}, max {}",
+            min,
+            self.lengths[self.lengths.len() / 2],
+            self.mean_length(),
+            max
+        )?;
+        let bucket_size = ((max - min) / 10 + 1).max(1);
+        let mut buckets = vec![0; (max - min) / bucket_size + 1];
+        for length in &self.lengths {
+            buckets[(length - min) / bucket_size] += 1;
+        }
+        let largest = *buckets.iter().max().unwrap();
+        for (i, count) in buckets.iter().enumerate() {
+            let start = min + i * bucket_size;
+            writeln!(
+                f,
+                "  {:>4}-{:<4} {:5.1}% {}",
+                start,
+                start + bucket_size - 1,
+                percent(*count, self.samples),
+                "#".repeat(count * 40 / largest)
+            )?;
+        }
+        Ok(())
+    }
+
+    fn write_chars(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total: usize = self.chars.values().sum();
+        let share = |filter: fn(&char) -> bool| {
+            let count = self
+                .chars
+                .iter()
+                .filter(|(c, _)| filter(c))
+                .map(|x| x.1)
+                .sum();
+            percent(count, total)
+        };
+        writeln!(
+            f,
+            "Characters: letters {:.1}%, digits {:.1}%, whitespace {:.1}%, symbols {:.1}%",
+            share(|c| c.is_alphabetic()),
+            share(|c| c.is_numeric()),
+            share(|c| c.is_whitespace()),
+            share(|c| !c.is_alphanumeric() && !c.is_whitespace()),
+        )?;
+        let mut chars: Vec<(&char, &usize)> = self.chars.iter().collect();
+        chars.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        for (label, symbols) in [("Top characters", false), ("Symbols", true)] {
+            let top: Vec<String> = chars
+                .iter()
+                .filter(|(c, _)| !symbols || (!c.is_alphanumeric() && !c.is_whitespace()))
+                .take(15)
+                .map(|(c, count)| format!("{:?} {:.1}%", c, percent(**count, total)))
+                .collect();
+            writeln!(f, "{}: {}", label, top.join(", "))?;
+        }
+        Ok(())
+    }
+
+    /// Lists every path as a tree, with its share among its siblings and how often it occurs
+    /// per sample.
+    fn write_paths(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Paths:")?;
+        let mut sibling_totals: HashMap<&[String], usize> = HashMap::new();
+        for (path, count) in &self.paths {
+            *sibling_totals.entry(&path[..path.len() - 1]).or_default() += count;
+        }
+        for (path, count) in &self.paths {
+            let siblings = sibling_totals[&path[..path.len() - 1]];
+            let label = format!("{}{}", "  ".repeat(path.len()), path.last().unwrap());
+            writeln!(
+                f,
+                "{:<40} {:5.1}% {:8.2} per sample",
+                label,
+                percent(*count, siblings),
+                *count as f64 / self.samples as f64
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn percent(count: usize, total: usize) -> f64 {
+    100.0 * count as f64 / total.max(1) as f64
+}
+
+impl fmt::Display for Preview {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} samples of {}, {} unique ({:.1}%)",
+            self.samples,
+            self.name,
+            self.unique,
+            percent(self.unique, self.samples)
+        )?;
+        writeln!(f)?;
+        writeln!(f, "Examples:")?;
+        for example in &self.examples {
+            writeln!(f, "  {}", example.replace('\n', "\n  "))?;
+        }
+        writeln!(f)?;
+        self.write_lengths(f)?;
+        writeln!(f)?;
+        self.write_chars(f)?;
+        writeln!(f)?;
+        self.write_paths(f)
+    }
+}
@@ -0,0 +1,41 @@
+use rand::rngs::StdRng;
+use rand::{Error, RngCore, SeedableRng};
+use std::cell::RefCell;
+
+thread_local! {
+    static RNG: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+}
+
+/// The random number generator all generators draw from. Like `rand::ThreadRng` it is a handle
+/// to a generator of the current thread, but that one can be seeded to reproduce a drill set.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GeneratorRng;
+
+/// Returns the generators' random number generator of the current thread.
+pub fn rng() -> GeneratorRng {
+    GeneratorRng
+}
+
+/// Seeds the random number generator of the current thread, so the same generators produce the
+/// same patterns again.
+pub fn seed(seed: u64) {
+    RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(seed));
+}
+
+impl RngCore for GeneratorRng {
+    fn next_u32(&mut self) -> u32 {
+        RNG.with(|rng| rng.borrow_mut().next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        RNG.with(|rng| rng.borrow_mut().next_u64())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        RNG.with(|rng| rng.borrow_mut().fill_bytes(dest))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        RNG.with(|rng| rng.borrow_mut().try_fill_bytes(dest))
+    }
+}
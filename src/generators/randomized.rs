@@ -1,7 +1,11 @@
+use crate::generators::enumeration::Patterns;
+use crate::generators::error::GeneratorError;
+use crate::generators::random;
+use crate::generators::snapshot::{GeneratorSnapshot, WeightedChild};
 use crate::generators::{TypingPattern, TypingPatternGenerator};
 use rand::prelude::SliceRandom;
-use rand::{thread_rng, Rng};
-use std::rc::Rc;
+use rand::Rng;
+use std::sync::Arc;
 
 #[derive(Debug)]
 pub struct OneOfStringsPatternGenerator {
@@ -10,53 +14,230 @@ pub struct OneOfStringsPatternGenerator {
 }
 
 impl OneOfStringsPatternGenerator {
-    pub fn new(name: &'static str, strings: Vec<&'static str>) -> Self {
-        OneOfStringsPatternGenerator {
+    pub fn new(name: &'static str, strings: Vec<&'static str>) -> Result<Self, GeneratorError> {
+        let generator = OneOfStringsPatternGenerator {
             name: name.to_string(),
             strings: strings.iter().map(|x| x.to_string()).collect(),
-        }
+        };
+        generator.check()?;
+        Ok(generator)
     }
 }
 
 impl TypingPatternGenerator for OneOfStringsPatternGenerator {
     fn generate(&self) -> TypingPattern {
-        let mut rng = thread_rng();
+        let mut rng = random::rng();
         let pattern = self.strings.choose(&mut rng).unwrap().clone();
         TypingPattern {
             name: self.name.clone(),
             pattern,
+            children: Vec::new(),
+        }
+    }
+
+    fn generate_into(&self, pattern: &mut String) {
+        let mut rng = random::rng();
+        pattern.push_str(self.strings.choose(&mut rng).unwrap());
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn snapshot(&self, _children: &[usize]) -> Option<GeneratorSnapshot> {
+        Some(GeneratorSnapshot::OneOfStrings {
+            name: self.name.clone(),
+            strings: self.strings.clone(),
+        })
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        vec![("strings", format!("{:?}", self.strings))]
+    }
+
+    fn min_length(&self, _children: &[usize]) -> usize {
+        self.strings
+            .iter()
+            .map(|s| s.chars().count())
+            .min()
+            .unwrap_or(0)
+    }
+
+    fn cardinality(&self, _children: &[Option<u128>]) -> Option<u128> {
+        Some(self.strings.len() as u128)
+    }
+
+    fn enumerate(&self) -> Option<Patterns> {
+        Some(Box::new(self.strings.clone().into_iter()))
+    }
+
+    fn check(&self) -> Result<(), GeneratorError> {
+        if self.strings.is_empty() {
+            return Err(GeneratorError::NoChoices {
+                generator: self.name.clone(),
+                reason: String::from("the list of strings is empty"),
+            });
         }
+        Ok(())
     }
 }
 
 #[derive(Debug)]
 pub struct WeightedPatternGenerator {
     pub name: String,
-    pub patterns: Vec<(f32, Rc<dyn TypingPatternGenerator>)>,
+    pub patterns: Vec<(f32, Arc<dyn TypingPatternGenerator>)>,
     pub total_weight: f32,
 }
 
 impl WeightedPatternGenerator {
-    pub fn new(name: &'static str, children: Vec<(f32, Rc<dyn TypingPatternGenerator>)>) -> Self {
+    pub fn new(
+        name: &'static str,
+        children: Vec<(f32, Arc<dyn TypingPatternGenerator>)>,
+    ) -> Result<Self, GeneratorError> {
         let total_weight = children.iter().map(|x| x.0).sum();
-        WeightedPatternGenerator {
+        let generator = WeightedPatternGenerator {
             name: name.to_string(),
             patterns: children,
             total_weight,
+        };
+        generator.check()?;
+        Ok(generator)
+    }
+
+    /// The items of `children`, one per child, that belong to children with a weight above zero.
+    fn chosen<'a, T: Clone>(&'a self, children: &'a [T]) -> impl Iterator<Item = T> + 'a {
+        self.patterns
+            .iter()
+            .zip(children)
+            .filter(|((weight, _), _)| *weight > 0.0)
+            .map(|(_, child)| child.clone())
+    }
+
+    fn choose(&self) -> &Arc<dyn TypingPatternGenerator> {
+        let mut rng = random::rng();
+        let mut random_number = rng.gen_range(0.0..self.total_weight);
+        for (weight, child) in &self.patterns {
+            random_number -= weight;
+            if random_number < 0.0 {
+                return child;
+            }
         }
+        // Rounding can leave a tiny remainder, which belongs to the last child that can be chosen.
+        let (_, child) = self.patterns.iter().rev().find(|x| x.0 > 0.0).unwrap();
+        child
     }
 }
 
 impl TypingPatternGenerator for WeightedPatternGenerator {
     fn generate(&self) -> TypingPattern {
-        let mut rng = thread_rng();
-        let mut random_number = rng.gen_range(0.0..self.total_weight);
-        for (weight, child) in &self.patterns {
-            random_number -= weight;
-            if random_number <= 0.0 {
-                return child.generate();
+        self.choose().generate()
+    }
+
+    fn generate_into(&self, pattern: &mut String) {
+        self.choose().generate_into(pattern)
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn snapshot(&self, children: &[usize]) -> Option<GeneratorSnapshot> {
+        Some(GeneratorSnapshot::Weighted {
+            name: self.name.clone(),
+            children: self
+                .patterns
+                .iter()
+                .zip(children)
+                .map(|((weight, _), child)| WeightedChild {
+                    weight: *weight,
+                    child: *child,
+                })
+                .collect(),
+        })
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        vec![(
+            "weights",
+            format!(
+                "{:?}",
+                self.patterns.iter().map(|x| x.0).collect::<Vec<f32>>()
+            ),
+        )]
+    }
+
+    fn children(&self) -> Vec<Arc<dyn TypingPatternGenerator>> {
+        self.patterns
+            .iter()
+            .map(|(_, child)| child.clone())
+            .collect()
+    }
+
+    fn min_length(&self, children: &[usize]) -> usize {
+        self.chosen(children).min().unwrap_or(0)
+    }
+
+    fn cardinality(&self, children: &[Option<u128>]) -> Option<u128> {
+        self.chosen(children)
+            .try_fold(0u128, |total, cardinality| total.checked_add(cardinality?))
+    }
+
+    fn enumerate(&self) -> Option<Patterns> {
+        let children = self.children();
+        let mut patterns: Vec<Patterns> = Vec::new();
+        for child in self.chosen(&children) {
+            patterns.push(child.enumerate()?);
+        }
+        Some(Box::new(patterns.into_iter().flatten()))
+    }
+
+    fn warnings(&self) -> Vec<String> {
+        self.patterns
+            .iter()
+            .enumerate()
+            .filter(|(_, (weight, _))| *weight == 0.0)
+            .map(|(i, (_, child))| {
+                format!(
+                    "child {} ({}) has a weight of zero and is never chosen",
+                    i,
+                    child.name()
+                )
+            })
+            .collect()
+    }
+
+    fn check(&self) -> Result<(), GeneratorError> {
+        if self.patterns.is_empty() {
+            return Err(GeneratorError::NoChoices {
+                generator: self.name.clone(),
+                reason: String::from("there are no children"),
+            });
+        }
+        for (i, (weight, child)) in self.patterns.iter().enumerate() {
+            if !weight.is_finite() || *weight < 0.0 {
+                return Err(GeneratorError::InvalidOption {
+                    generator: self.name.clone(),
+                    option: format!("weight of child {} ({})", i, child.name()),
+                    value: weight.to_string(),
+                    reason: String::from("weights are finite and not negative"),
+                });
             }
         }
-        panic!("Failed to generate a pattern");
+        let sum: f32 = self.patterns.iter().map(|x| x.0).sum();
+        if sum <= 0.0 {
+            return Err(GeneratorError::NoChoices {
+                generator: self.name.clone(),
+                reason: String::from("all weights are zero"),
+            });
+        }
+        if (sum - self.total_weight).abs() > f32::EPSILON * sum.max(1.0) {
+            return Err(GeneratorError::InvalidOption {
+                generator: self.name.clone(),
+                option: String::from("total_weight"),
+                value: self.total_weight.to_string(),
+                reason: format!("the weights add up to {}", sum),
+            });
+        }
+        Ok(())
     }
 }
@@ -0,0 +1,157 @@
+use crate::generators::error::GeneratorError;
+use crate::generators::helpers::{check_options, config_value, required_config_value};
+use crate::generators::random::{self, GeneratorRng};
+use crate::generators::{TypingPattern, TypingPatternGenerator};
+use rand::Rng;
+use regex_syntax::hir::{Class, ClassUnicode, ClassUnicodeRange, Hir, HirKind};
+use regex_syntax::ParserBuilder;
+use std::collections::HashMap;
+
+/// Generates random strings matching a regular expression, e.g. `[a-z]+_[0-9]{2,4}`.
+///
+/// Unbounded quantifiers (`*`, `+`, `{n,}`) repeat at most `max_repeat` times.
+/// Character classes prefer printable ASCII, so `.` or `\w` yield characters you can actually type.
+/// Anchors and word boundaries (`^`, `$`, `\b`, ...) are ignored, so a regex that only matches
+/// thanks to them, like `a\bb`, gives patterns it doesn't match. Look-ahead and look-behind
+/// aren't supported by regex-syntax and are rejected.
+#[derive(Debug)]
+pub struct RegexPatternGenerator {
+    pub name: String,
+    pub regex: String,
+    pub max_repeat: u32,
+    hir: Hir,
+}
+
+impl RegexPatternGenerator {
+    pub fn new(name: &'static str, config: HashMap<&str, String>) -> Result<Self, GeneratorError> {
+        check_options(name, &config, &["regex", "max_repeat"])?;
+        let regex: String = required_config_value(name, &config, "regex")?;
+        let max_repeat = config_value(name, &config, "max_repeat", 4)?;
+        let invalid_regex = |reason: String| GeneratorError::InvalidOption {
+            generator: name.to_string(),
+            option: String::from("regex"),
+            value: regex.clone(),
+            reason,
+        };
+        let hir = ParserBuilder::new()
+            .build()
+            .parse(&regex)
+            .map_err(|e| invalid_regex(e.to_string()))?;
+        // Empty classes like `[^\s\S]` have nothing to pick from.
+        if hir.properties().minimum_len().is_none() {
+            return Err(invalid_regex(String::from("the regex matches nothing")));
+        }
+        Ok(RegexPatternGenerator {
+            name: name.to_string(),
+            regex,
+            max_repeat,
+            hir,
+        })
+    }
+
+    fn generate_hir(&self, hir: &Hir, rng: &mut GeneratorRng, pattern: &mut String) {
+        match hir.kind() {
+            HirKind::Empty | HirKind::Look(_) => {}
+            HirKind::Literal(literal) => pattern.push_str(&String::from_utf8_lossy(&literal.0)),
+            HirKind::Class(Class::Unicode(class)) => pattern.push(random_char(class, rng)),
+            HirKind::Class(Class::Bytes(class)) => {
+                let bytes: Vec<u8> = class
+                    .ranges()
+                    .iter()
+                    .flat_map(|r| r.start()..=r.end())
+                    .collect();
+                pattern.push(bytes[rng.gen_range(0..bytes.len())] as char);
+            }
+            HirKind::Repetition(repetition) => {
+                let max = repetition
+                    .max
+                    .unwrap_or_else(|| self.max_repeat.max(repetition.min));
+                let count = rng.gen_range(repetition.min..=max);
+                for _ in 0..count {
+                    self.generate_hir(&repetition.sub, rng, pattern);
+                }
+            }
+            HirKind::Capture(capture) => self.generate_hir(&capture.sub, rng, pattern),
+            HirKind::Concat(children) => {
+                for child in children {
+                    self.generate_hir(child, rng, pattern);
+                }
+            }
+            HirKind::Alternation(children) => {
+                let child = &children[rng.gen_range(0..children.len())];
+                self.generate_hir(child, rng, pattern);
+            }
+        }
+    }
+}
+
+/// The minimum number of chars in a string matching `hir`.
+fn min_chars(hir: &Hir) -> usize {
+    match hir.kind() {
+        HirKind::Empty | HirKind::Look(_) => 0,
+        HirKind::Literal(literal) => String::from_utf8_lossy(&literal.0).chars().count(),
+        HirKind::Class(_) => 1,
+        HirKind::Repetition(repetition) => {
+            (repetition.min as usize).saturating_mul(min_chars(&repetition.sub))
+        }
+        HirKind::Capture(capture) => min_chars(&capture.sub),
+        HirKind::Concat(children) => children.iter().map(min_chars).sum(),
+        HirKind::Alternation(children) => children.iter().map(min_chars).min().unwrap_or(0),
+    }
+}
+
+/// Picks a random character from `class`, restricted to printable ASCII whenever the class allows it.
+fn random_char(class: &ClassUnicode, rng: &mut GeneratorRng) -> char {
+    let mut printable = ClassUnicode::new([ClassUnicodeRange::new(' ', '~')]);
+    printable.intersect(class);
+    let class = if printable.ranges().is_empty() {
+        class
+    } else {
+        &printable
+    };
+
+    let total: u32 = class
+        .ranges()
+        .iter()
+        .map(|r| r.end() as u32 - r.start() as u32 + 1)
+        .sum();
+    let mut index = rng.gen_range(0..total);
+    for range in class.ranges() {
+        let size = range.end() as u32 - range.start() as u32 + 1;
+        if index < size {
+            // Wide non-ASCII ranges can span the surrogate block, which has no chars.
+            return char::from_u32(range.start() as u32 + index).unwrap_or(range.start());
+        }
+        index -= size;
+    }
+    unreachable!("index is always within the class ranges")
+}
+
+impl TypingPatternGenerator for RegexPatternGenerator {
+    fn generate(&self) -> TypingPattern {
+        let mut rng = random::rng();
+        let mut pattern = String::new();
+        self.generate_hir(&self.hir, &mut rng, &mut pattern);
+
+        TypingPattern {
+            name: self.name.clone(),
+            pattern,
+            children: Vec::new(),
+        }
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("regex", format!("{:?}", self.regex)),
+            ("max_repeat", self.max_repeat.to_string()),
+        ]
+    }
+
+    fn min_length(&self, _children: &[usize]) -> usize {
+        min_chars(&self.hir)
+    }
+}
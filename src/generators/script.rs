@@ -0,0 +1,319 @@
+use crate::generators::error::GeneratorError;
+use crate::generators::helpers::{check_options, config_value};
+use crate::generators::random;
+use crate::generators::snapshot::GeneratorSnapshot;
+use crate::generators::{TypingPattern, TypingPatternGenerator};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rhai::{ASTNode, Array, Dynamic, Engine, EvalAltResult, Expr, Stmt, AST};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::sync::{Arc, Mutex};
+
+thread_local! {
+    /// The patterns generated for each script running on this thread, innermost script last.
+    static CALLS: RefCell<Vec<Vec<TypingPattern>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Generates patterns with a Rhai script, for drills that are easier to write as code than as a
+/// tree of generators. For example, a function signature with one to three typed parameters:
+///
+/// ```rhai
+/// let params = [];
+/// for i in 0..random(1, 3) {
+///     params.push(generate("symbols") + ": " + choose(["i32", "String", "&str"]));
+/// }
+/// "fn " + generate("symbols") + "(" + join(params, ", ") + ")"
+/// ```
+///
+/// A script returns a string, or an array whose items are joined. Besides Rhai's standard
+/// library it can call:
+///
+/// - `generate(name)`: a pattern of one of `generators`, which becomes a child of the result
+/// - `random(min, max)`: an integer from `min` to `max`, inclusive
+/// - `chance(probability)`: true with the given probability
+/// - `choose(array)`: a random item
+/// - `join(array, delimiter)`
+///
+/// Random numbers come from `random::rng()`, so seeding reproduces scripted patterns too.
+/// Scripts stop after `max_operations` operations, so a runaway loop fails instead of hanging.
+///
+/// Calls to unknown functions, and `generate` with an unknown name, are rejected when the
+/// script is compiled. Other errors only show when the script runs: the pattern is then empty
+/// and `last_error` tells what went wrong.
+#[derive(Debug)]
+pub struct ScriptGenerator {
+    pub name: String,
+    pub script: String,
+    pub generators: Vec<Arc<dyn TypingPatternGenerator>>,
+    pub max_operations: u64,
+    engine: Engine,
+    ast: AST,
+    last_error: Mutex<Option<String>>,
+}
+
+impl ScriptGenerator {
+    /// Takes the script from the option `script`, or from the file at `path`.
+    pub fn new(
+        name: &'static str,
+        generators: Vec<Arc<dyn TypingPatternGenerator>>,
+        config: HashMap<&str, String>,
+    ) -> Result<Self, GeneratorError> {
+        check_options(name, &config, &["script", "path", "max_operations"])?;
+        let script = match (config.get("script"), config.get("path")) {
+            (Some(script), None) => script.clone(),
+            (None, Some(path)) => {
+                fs::read_to_string(path).map_err(|e| GeneratorError::InvalidOption {
+                    generator: name.to_string(),
+                    option: String::from("path"),
+                    value: path.clone(),
+                    reason: e.to_string(),
+                })?
+            }
+            (Some(_), Some(path)) => {
+                return Err(GeneratorError::InvalidOption {
+                    generator: name.to_string(),
+                    option: String::from("path"),
+                    value: path.clone(),
+                    reason: String::from("give either script or path, not both"),
+                })
+            }
+            (None, None) => {
+                return Err(GeneratorError::MissingOption {
+                    generator: name.to_string(),
+                    option: String::from("script"),
+                })
+            }
+        };
+        let max_operations = config_value(name, &config, "max_operations", 100_000)?;
+        let generator = ScriptGenerator::from_script(name, script, generators, max_operations)?;
+        generator.check()?;
+        Ok(generator)
+    }
+
+    /// Compiles `script` without running it, checking the functions it calls.
+    pub fn from_script(
+        name: &str,
+        script: String,
+        generators: Vec<Arc<dyn TypingPatternGenerator>>,
+        max_operations: u64,
+    ) -> Result<Self, GeneratorError> {
+        let mut engine = Engine::new();
+        engine.set_max_operations(max_operations);
+        register_functions(&mut engine, &generators);
+        let ast = engine
+            .compile(&script)
+            .map_err(|e| GeneratorError::Script {
+                generator: name.to_string(),
+                reason: e.to_string(),
+            })?;
+        check_calls(&engine, &ast, &generators).map_err(|reason| GeneratorError::Script {
+            generator: name.to_string(),
+            reason,
+        })?;
+        Ok(ScriptGenerator {
+            name: name.to_string(),
+            script,
+            generators,
+            max_operations,
+            engine,
+            ast,
+            last_error: Mutex::new(None),
+        })
+    }
+
+    /// Why the script failed the last time it ran, if it did.
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error.lock().unwrap().clone()
+    }
+
+    /// Runs the script, returning its pattern and the patterns it generated on the way.
+    fn run(&self) -> Result<(String, Vec<TypingPattern>), Box<EvalAltResult>> {
+        CALLS.with(|calls| calls.borrow_mut().push(Vec::new()));
+        let result = self.engine.eval_ast::<Dynamic>(&self.ast);
+        let children = CALLS.with(|calls| calls.borrow_mut().pop().unwrap_or_default());
+        let result = result?;
+        let pattern = if result.is_array() {
+            join(result.cast::<Array>(), "")
+        } else if result.is_string() {
+            result.into_string()?
+        } else {
+            return Err(format!(
+                "scripts return a string or an array, not {}",
+                result.type_name()
+            )
+            .into());
+        };
+        Ok((pattern, children))
+    }
+}
+
+fn join(items: Array, delimiter: &str) -> String {
+    items
+        .iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<String>>()
+        .join(delimiter)
+}
+
+/// Functions Rhai handles itself instead of registering them.
+const KEYWORD_FUNCTIONS: [&str; 10] = [
+    "print",
+    "debug",
+    "type_of",
+    "eval",
+    "Fn",
+    "call",
+    "curry",
+    "is_shared",
+    "is_def_fn",
+    "is_def_var",
+];
+
+/// Finds calls to functions that are neither registered nor defined by the script, and calls to
+/// `generate` with a literal name that isn't one of `generators`.
+fn check_calls(
+    engine: &Engine,
+    ast: &AST,
+    generators: &[Arc<dyn TypingPatternGenerator>],
+) -> Result<(), String> {
+    let mut functions: HashSet<String> = engine
+        .gen_fn_signatures(true)
+        .iter()
+        .filter_map(|signature| signature.split('(').next())
+        .map(String::from)
+        .collect();
+    functions.extend(ast.iter_functions().map(|f| f.name.to_string()));
+    functions.extend(KEYWORD_FUNCTIONS.iter().map(|f| f.to_string()));
+    let names: HashSet<String> = generators.iter().map(|g| g.name()).collect();
+    let mut error = None;
+    ast.walk(&mut |path: &[ASTNode]| {
+        let (call, position) = match path.last() {
+            Some(ASTNode::Expr(Expr::FnCall(call, position)))
+            | Some(ASTNode::Stmt(Stmt::FnCall(call, position))) => (call, position),
+            _ => return true,
+        };
+        if call.op_token.is_some() || call.is_qualified() {
+            return true;
+        }
+        if !functions.contains(call.name.as_str()) {
+            error = Some(format!(
+                "there is no function {}() ({})",
+                call.name, position
+            ));
+        } else if let (true, [Expr::StringConstant(name, _)]) =
+            (call.name == "generate", call.args.as_slice())
+        {
+            if !names.contains(name.as_str()) {
+                error = Some(format!(
+                    "there is no generator named {:?} ({})",
+                    name, position
+                ));
+            }
+        }
+        error.is_none()
+    });
+    error.map_or(Ok(()), Err)
+}
+
+fn register_functions(engine: &mut Engine, generators: &[Arc<dyn TypingPatternGenerator>]) {
+    let named: HashMap<String, Arc<dyn TypingPatternGenerator>> = generators
+        .iter()
+        .map(|generator| (generator.name(), generator.clone()))
+        .collect();
+    engine.register_fn(
+        "generate",
+        move |name: &str| -> Result<String, Box<EvalAltResult>> {
+            let generator = named
+                .get(name)
+                .ok_or_else(|| format!("there is no generator named {:?}", name))?;
+            let generated = generator.generate();
+            let pattern = generated.pattern.clone();
+            CALLS.with(|calls| {
+                if let Some(children) = calls.borrow_mut().last_mut() {
+                    children.push(generated);
+                }
+            });
+            Ok(pattern)
+        },
+    );
+    engine.register_fn(
+        "random",
+        |min: i64, max: i64| -> Result<i64, Box<EvalAltResult>> {
+            if min > max {
+                return Err(format!("random({}, {}) has an empty range", min, max).into());
+            }
+            Ok(random::rng().gen_range(min..=max))
+        },
+    );
+    engine.register_fn(
+        "chance",
+        |probability: f64| -> Result<bool, Box<EvalAltResult>> {
+            if !(0.0..=1.0).contains(&probability) {
+                return Err(format!("chance({}) is not a probability", probability).into());
+            }
+            Ok(random::rng().gen_bool(probability))
+        },
+    );
+    engine.register_fn(
+        "choose",
+        |items: Array| -> Result<Dynamic, Box<EvalAltResult>> {
+            items
+                .choose(&mut random::rng())
+                .cloned()
+                .ok_or_else(|| "choose() got an empty array".into())
+        },
+    );
+    engine.register_fn("join", join);
+}
+
+impl TypingPatternGenerator for ScriptGenerator {
+    /// Gives an empty pattern if the script fails, and keeps the error for `last_error`.
+    fn generate(&self) -> TypingPattern {
+        let (pattern, children) = match self.run() {
+            Ok(generated) => generated,
+            Err(e) => {
+                *self.last_error.lock().unwrap() = Some(e.to_string());
+                (String::new(), Vec::new())
+            }
+        };
+        TypingPattern {
+            name: self.name.clone(),
+            pattern,
+            children,
+        }
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn snapshot(&self, children: &[usize]) -> Option<GeneratorSnapshot> {
+        Some(GeneratorSnapshot::Script {
+            name: self.name.clone(),
+            script: self.script.clone(),
+            generators: children.to_vec(),
+            max_operations: self.max_operations,
+        })
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("script", format!("{:?}", self.script)),
+            ("max_operations", self.max_operations.to_string()),
+        ]
+    }
+
+    fn children(&self) -> Vec<Arc<dyn TypingPatternGenerator>> {
+        self.generators.clone()
+    }
+
+    /// Runs the script once, which catches most mistakes but not ones in rarely taken branches.
+    fn check(&self) -> Result<(), GeneratorError> {
+        self.run().map(|_| ()).map_err(|e| GeneratorError::Script {
+            generator: self.name.clone(),
+            reason: e.to_string(),
+        })
+    }
+}
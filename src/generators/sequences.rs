@@ -1,12 +1,37 @@
-use crate::generators::{TypingPattern, TypingPatternGenerator};
-use rand::{thread_rng, Rng};
+use crate::generators::enumeration::{repeat_cardinality, Patterns, Product};
+use crate::generators::error::GeneratorError;
+use crate::generators::helpers::{check_options, config_value, uppercase_first_letter_from};
+use crate::generators::random;
+use crate::generators::snapshot::GeneratorSnapshot;
+use crate::generators::{joined_length, TypingPattern, TypingPatternGenerator};
+use rand::Rng;
 use std::collections::HashMap;
-use std::rc::Rc;
+use std::sync::Arc;
+
+/// Appends `count` patterns of `child` to `pattern`, separated by `delimiter`.
+fn repeat_into(
+    child: &Arc<dyn TypingPatternGenerator>,
+    count: u32,
+    delimiter: &str,
+    camel_case_strings: bool,
+    pattern: &mut String,
+) {
+    for i in 0..count {
+        if i > 0 {
+            pattern.push_str(delimiter);
+        }
+        let start = pattern.len();
+        child.generate_into(pattern);
+        if camel_case_strings {
+            uppercase_first_letter_from(pattern, start);
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct RepeatPatternGenerator {
     pub name: String,
-    pub pattern: Rc<dyn TypingPatternGenerator>,
+    pub pattern: Arc<dyn TypingPatternGenerator>,
     pub count: u32,
     pub delimiter: String,
     pub camel_case_strings: bool,
@@ -15,30 +40,20 @@ pub struct RepeatPatternGenerator {
 impl RepeatPatternGenerator {
     pub fn new(
         name: &'static str,
-        child: Rc<dyn TypingPatternGenerator>,
+        child: Arc<dyn TypingPatternGenerator>,
         config: HashMap<&str, String>,
-    ) -> Self {
-        let count = config
-            .get("count")
-            .unwrap_or(&String::from("4"))
-            .parse::<u32>()
-            .unwrap_or(4);
-        let delimiter = config
-            .get("delimiter")
-            .unwrap_or(&String::from(" "))
-            .to_string();
-        let camel_case_strings = config
-            .get("camel_case_strings")
-            .unwrap_or(&String::from("false"))
-            .parse::<bool>()
-            .unwrap_or(false);
-        RepeatPatternGenerator {
+    ) -> Result<Self, GeneratorError> {
+        check_options(name, &config, &["count", "delimiter", "camel_case_strings"])?;
+        let count = config_value(name, &config, "count", 4)?;
+        let delimiter = config_value(name, &config, "delimiter", String::from(" "))?;
+        let camel_case_strings = config_value(name, &config, "camel_case_strings", false)?;
+        Ok(RepeatPatternGenerator {
             name: name.to_string(),
             pattern: child,
             count,
             delimiter,
             camel_case_strings,
-        }
+        })
     }
 }
 
@@ -63,14 +78,68 @@ impl TypingPatternGenerator for RepeatPatternGenerator {
         TypingPattern {
             name: self.name.clone(),
             pattern,
+            children: generated_patterns,
         }
     }
+
+    fn generate_into(&self, pattern: &mut String) {
+        repeat_into(
+            &self.pattern,
+            self.count,
+            &self.delimiter,
+            self.camel_case_strings,
+            pattern,
+        );
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn snapshot(&self, children: &[usize]) -> Option<GeneratorSnapshot> {
+        Some(GeneratorSnapshot::Repeat {
+            name: self.name.clone(),
+            child: children[0],
+            count: self.count,
+            delimiter: self.delimiter.clone(),
+            camel_case_strings: self.camel_case_strings,
+        })
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("count", self.count.to_string()),
+            ("delimiter", format!("{:?}", self.delimiter)),
+            ("camel_case_strings", self.camel_case_strings.to_string()),
+        ]
+    }
+
+    fn children(&self) -> Vec<Arc<dyn TypingPatternGenerator>> {
+        vec![self.pattern.clone()]
+    }
+
+    fn min_length(&self, children: &[usize]) -> usize {
+        joined_length(self.count as usize, children[0], &self.delimiter)
+    }
+
+    fn cardinality(&self, children: &[Option<u128>]) -> Option<u128> {
+        repeat_cardinality(children[0], self.count)
+    }
+
+    fn enumerate(&self) -> Option<Patterns> {
+        Some(Box::new(Product::new(
+            vec![self.pattern.clone(); self.count as usize],
+            &self.delimiter,
+            self.camel_case_strings,
+        )?))
+    }
 }
 
-#[derive(Debug)]
+/// Repeats `pattern` between `min_count` (inclusive) and `max_count` (exclusive) times.
+#[derive(Debug, Clone)]
 pub struct RandomRepeatGenerator {
     pub name: String,
-    pub pattern: Rc<dyn TypingPatternGenerator>,
+    pub pattern: Arc<dyn TypingPatternGenerator>,
     pub min_count: u32,
     pub max_count: u32,
     pub delimiter: String,
@@ -80,42 +149,34 @@ pub struct RandomRepeatGenerator {
 impl RandomRepeatGenerator {
     pub fn new(
         name: &'static str,
-        child: Rc<dyn TypingPatternGenerator>,
+        child: Arc<dyn TypingPatternGenerator>,
         config: HashMap<&str, String>,
-    ) -> Self {
-        let min_count = config
-            .get("min_count")
-            .unwrap_or(&String::from("2"))
-            .parse::<u32>()
-            .unwrap_or(1);
-        let max_count = config
-            .get("max_count")
-            .unwrap_or(&String::from("4"))
-            .parse::<u32>()
-            .unwrap_or(4);
-        let delimiter = config
-            .get("delimiter")
-            .unwrap_or(&String::from(" "))
-            .to_string();
-        let camel_case_strings = config
-            .get("camel_case_strings")
-            .unwrap_or(&String::from("false"))
-            .parse::<bool>()
-            .unwrap_or(false);
-        RandomRepeatGenerator {
+    ) -> Result<Self, GeneratorError> {
+        check_options(
+            name,
+            &config,
+            &["min_count", "max_count", "delimiter", "camel_case_strings"],
+        )?;
+        let min_count = config_value(name, &config, "min_count", 2)?;
+        let max_count = config_value(name, &config, "max_count", 4)?;
+        let delimiter = config_value(name, &config, "delimiter", String::from(" "))?;
+        let camel_case_strings = config_value(name, &config, "camel_case_strings", false)?;
+        let generator = RandomRepeatGenerator {
             name: name.to_string(),
             pattern: child,
             min_count,
             max_count,
             delimiter,
             camel_case_strings,
-        }
+        };
+        generator.check()?;
+        Ok(generator)
     }
 }
 
 impl TypingPatternGenerator for RandomRepeatGenerator {
     fn generate(&self) -> TypingPattern {
-        let mut rng = thread_rng();
+        let mut rng = random::rng();
         let count = rng.gen_range(self.min_count..self.max_count);
         let mut generated_patterns: Vec<TypingPattern> = Vec::new();
         for _ in 0..count {
@@ -136,6 +197,98 @@ impl TypingPatternGenerator for RandomRepeatGenerator {
         TypingPattern {
             name: self.name.clone(),
             pattern,
+            children: generated_patterns,
         }
     }
+
+    fn generate_into(&self, pattern: &mut String) {
+        let mut rng = random::rng();
+        let count = rng.gen_range(self.min_count..self.max_count);
+        repeat_into(
+            &self.pattern,
+            count,
+            &self.delimiter,
+            self.camel_case_strings,
+            pattern,
+        );
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn snapshot(&self, children: &[usize]) -> Option<GeneratorSnapshot> {
+        Some(GeneratorSnapshot::RandomRepeat {
+            name: self.name.clone(),
+            child: children[0],
+            min_count: self.min_count,
+            max_count: self.max_count,
+            delimiter: self.delimiter.clone(),
+            camel_case_strings: self.camel_case_strings,
+        })
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("min_count", self.min_count.to_string()),
+            ("max_count", self.max_count.to_string()),
+            ("delimiter", format!("{:?}", self.delimiter)),
+            ("camel_case_strings", self.camel_case_strings.to_string()),
+        ]
+    }
+
+    fn children(&self) -> Vec<Arc<dyn TypingPatternGenerator>> {
+        vec![self.pattern.clone()]
+    }
+
+    fn min_length(&self, children: &[usize]) -> usize {
+        joined_length(self.min_count as usize, children[0], &self.delimiter)
+    }
+
+    fn cardinality(&self, children: &[Option<u128>]) -> Option<u128> {
+        (self.min_count..self.max_count).try_fold(0u128, |total, count| {
+            total.checked_add(repeat_cardinality(children[0], count)?)
+        })
+    }
+
+    fn enumerate(&self) -> Option<Patterns> {
+        // Products are created later, so find out now if the child can be enumerated.
+        let _ = self.pattern.enumerate()?;
+        let pattern = self.pattern.clone();
+        let delimiter = self.delimiter.clone();
+        let camel_case_strings = self.camel_case_strings;
+        Some(Box::new((self.min_count..self.max_count).flat_map(
+            move |count| {
+                Product::new(
+                    vec![pattern.clone(); count as usize],
+                    &delimiter,
+                    camel_case_strings,
+                )
+                .unwrap()
+            },
+        )))
+    }
+
+    fn check(&self) -> Result<(), GeneratorError> {
+        if self.min_count >= self.max_count {
+            return Err(GeneratorError::InvalidOption {
+                generator: self.name.clone(),
+                option: String::from("max_count"),
+                value: self.max_count.to_string(),
+                reason: format!(
+                    "max_count is exclusive, so it has to be greater than min_count ({})",
+                    self.min_count
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    fn with_range(&self, min: u32, max: u32) -> Option<Arc<dyn TypingPatternGenerator>> {
+        Some(Arc::new(RandomRepeatGenerator {
+            min_count: min,
+            max_count: max,
+            ..self.clone()
+        }))
+    }
 }
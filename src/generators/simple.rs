@@ -1,7 +1,10 @@
+use crate::generators::enumeration::{Patterns, Product};
+use crate::generators::error::GeneratorError;
+use crate::generators::helpers::{check_options, config_value};
+use crate::generators::snapshot::GeneratorSnapshot;
 use crate::generators::{TypingPattern, TypingPatternGenerator};
-use std::cell::RefCell;
 use std::collections::HashMap;
-use std::rc::Rc;
+use std::sync::Arc;
 
 #[derive(Debug)]
 pub struct SingleStringGenerator {
@@ -23,32 +26,62 @@ impl TypingPatternGenerator for SingleStringGenerator {
         TypingPattern {
             name: self.name.clone(),
             pattern: self.pattern.clone(),
+            children: Vec::new(),
         }
     }
+
+    fn generate_into(&self, pattern: &mut String) {
+        pattern.push_str(&self.pattern);
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn snapshot(&self, _children: &[usize]) -> Option<GeneratorSnapshot> {
+        Some(GeneratorSnapshot::SingleString {
+            name: self.name.clone(),
+            pattern: self.pattern.clone(),
+        })
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        vec![("pattern", format!("{:?}", self.pattern))]
+    }
+
+    fn min_length(&self, _children: &[usize]) -> usize {
+        self.pattern.chars().count()
+    }
+
+    fn cardinality(&self, _children: &[Option<u128>]) -> Option<u128> {
+        Some(1)
+    }
+
+    fn enumerate(&self) -> Option<Patterns> {
+        Some(Box::new(std::iter::once(self.pattern.clone())))
+    }
 }
 
 #[derive(Debug)]
 pub struct ListOfPatternsGenerator {
     pub name: String,
-    pub patterns: Vec<Rc<dyn TypingPatternGenerator>>,
+    pub patterns: Vec<Arc<dyn TypingPatternGenerator>>,
     pub delimiter: String,
 }
 
 impl ListOfPatternsGenerator {
     pub fn new(
         name: &'static str,
-        children: Vec<Rc<dyn TypingPatternGenerator>>,
+        children: Vec<Arc<dyn TypingPatternGenerator>>,
         config: HashMap<&str, String>,
-    ) -> Self {
-        let delimiter = config
-            .get("delimiter")
-            .unwrap_or(&String::from(" "))
-            .to_string();
-        ListOfPatternsGenerator {
+    ) -> Result<Self, GeneratorError> {
+        check_options(name, &config, &["delimiter"])?;
+        let delimiter = config_value(name, &config, "delimiter", String::from(" "))?;
+        Ok(ListOfPatternsGenerator {
             name: name.to_string(),
             patterns: children,
             delimiter,
-        }
+        })
     }
 }
 
@@ -67,12 +100,57 @@ impl TypingPatternGenerator for ListOfPatternsGenerator {
         TypingPattern {
             name: self.name.clone(),
             pattern,
+            children: generated_patterns,
         }
     }
-}
 
-fn test() {
-    let foo = Box::new(SingleStringGenerator::new("foo", "foo"));
-    let foo_ = RefCell::new(foo);
-    foo_.generate();
+    fn generate_into(&self, pattern: &mut String) {
+        for (i, child) in self.patterns.iter().enumerate() {
+            if i > 0 {
+                pattern.push_str(&self.delimiter);
+            }
+            child.generate_into(pattern);
+        }
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn snapshot(&self, children: &[usize]) -> Option<GeneratorSnapshot> {
+        Some(GeneratorSnapshot::ListOfPatterns {
+            name: self.name.clone(),
+            children: children.to_vec(),
+            delimiter: self.delimiter.clone(),
+        })
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        vec![("delimiter", format!("{:?}", self.delimiter))]
+    }
+
+    fn children(&self) -> Vec<Arc<dyn TypingPatternGenerator>> {
+        self.patterns.clone()
+    }
+
+    fn min_length(&self, children: &[usize]) -> usize {
+        let delimiters = self.delimiter.chars().count() * children.len().saturating_sub(1);
+        children
+            .iter()
+            .fold(delimiters, |total, length| total.saturating_add(*length))
+    }
+
+    fn cardinality(&self, children: &[Option<u128>]) -> Option<u128> {
+        children.iter().try_fold(1u128, |total, cardinality| {
+            total.checked_mul((*cardinality)?)
+        })
+    }
+
+    fn enumerate(&self) -> Option<Patterns> {
+        Some(Box::new(Product::new(
+            self.patterns.clone(),
+            &self.delimiter,
+            false,
+        )?))
+    }
 }
@@ -0,0 +1,392 @@
+use crate::generators::blocks::BlockGenerator;
+use crate::generators::coding::{
+    NumberFormat, NumberPatternGenerator, StringLiteralGenerator, StringLiteralStyle,
+};
+use crate::generators::error::GeneratorError;
+use crate::generators::expressions::{find_operator, Arity, ExpressionGenerator};
+use crate::generators::nesting::BalancedNestingGenerator;
+use crate::generators::randomized::{OneOfStringsPatternGenerator, WeightedPatternGenerator};
+use crate::generators::script::ScriptGenerator;
+use crate::generators::sequences::{RandomRepeatGenerator, RepeatPatternGenerator};
+use crate::generators::simple::{ListOfPatternsGenerator, SingleStringGenerator};
+use crate::generators::template::TemplateGenerator;
+use crate::generators::TypingPatternGenerator;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// The version of the snapshot format, bumped on incompatible changes.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// A generator graph in serializable form. Every generator is listed once and refers to its
+/// children by their index in `generators`, so shared generators stay shared.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraphSnapshot {
+    pub version: u32,
+    pub root: usize,
+    pub named: Vec<usize>,
+    pub generators: Vec<GeneratorSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeightedChild {
+    pub weight: f32,
+    pub child: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperatorSnapshot {
+    pub symbol: String,
+    pub arity: Arity,
+}
+
+/// The settings of one generator, with children as indices into `GraphSnapshot::generators`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GeneratorSnapshot {
+    SingleString {
+        name: String,
+        pattern: String,
+    },
+    ListOfPatterns {
+        name: String,
+        children: Vec<usize>,
+        delimiter: String,
+    },
+    OneOfStrings {
+        name: String,
+        strings: Vec<String>,
+    },
+    Weighted {
+        name: String,
+        children: Vec<WeightedChild>,
+    },
+    Repeat {
+        name: String,
+        child: usize,
+        count: u32,
+        delimiter: String,
+        camel_case_strings: bool,
+    },
+    RandomRepeat {
+        name: String,
+        child: usize,
+        min_count: u32,
+        max_count: u32,
+        delimiter: String,
+        camel_case_strings: bool,
+    },
+    Number {
+        name: String,
+        format: NumberFormat,
+        min_length: u32,
+        max_length: u32,
+        range: Option<(i64, i64)>,
+        leading_zeros: bool,
+        uppercase: bool,
+        negative_probability: f64,
+        min_fraction_length: u32,
+        max_fraction_length: u32,
+        min_exponent: i32,
+        max_exponent: i32,
+        digit_separator: String,
+        separator_group: usize,
+        suffixes: Vec<String>,
+    },
+    StringLiteral {
+        name: String,
+        child: usize,
+        interpolation: Option<usize>,
+        styles: Vec<StringLiteralStyle>,
+        escape_probability: f64,
+        interpolation_probability: f64,
+    },
+    Expression {
+        name: String,
+        operands: Vec<usize>,
+        members: Option<usize>,
+        operators: Vec<OperatorSnapshot>,
+        min_depth: u32,
+        max_depth: u32,
+        always_parenthesize: bool,
+    },
+    Block {
+        name: String,
+        header: usize,
+        body: usize,
+        min_lines: u32,
+        max_lines: u32,
+        open: String,
+        close: String,
+    },
+    BalancedNesting {
+        name: String,
+        content: Option<usize>,
+        pairs: Vec<(char, char)>,
+        min_depth: u32,
+        max_depth: u32,
+        max_siblings: u32,
+        content_probability: f64,
+        delimiter: String,
+        closing_runs: bool,
+        terminator: String,
+        terminator_probability: f64,
+    },
+    Script {
+        name: String,
+        script: String,
+        generators: Vec<usize>,
+        max_operations: u64,
+    },
+    Template {
+        name: String,
+        template: String,
+        generators: Vec<usize>,
+    },
+}
+
+impl GeneratorSnapshot {
+    pub fn name(&self) -> String {
+        match self {
+            GeneratorSnapshot::SingleString { name, .. }
+            | GeneratorSnapshot::ListOfPatterns { name, .. }
+            | GeneratorSnapshot::OneOfStrings { name, .. }
+            | GeneratorSnapshot::Weighted { name, .. }
+            | GeneratorSnapshot::Repeat { name, .. }
+            | GeneratorSnapshot::RandomRepeat { name, .. }
+            | GeneratorSnapshot::StringLiteral { name, .. }
+            | GeneratorSnapshot::Expression { name, .. }
+            | GeneratorSnapshot::Block { name, .. }
+            | GeneratorSnapshot::BalancedNesting { name, .. }
+            | GeneratorSnapshot::Number { name, .. }
+            | GeneratorSnapshot::Script { name, .. }
+            | GeneratorSnapshot::Template { name, .. } => name.clone(),
+        }
+    }
+
+    /// The indices of the children, in the order `build` expects them.
+    pub fn children(&self) -> Vec<usize> {
+        match self {
+            GeneratorSnapshot::SingleString { .. }
+            | GeneratorSnapshot::OneOfStrings { .. }
+            | GeneratorSnapshot::Number { .. } => Vec::new(),
+            GeneratorSnapshot::ListOfPatterns { children, .. } => children.clone(),
+            GeneratorSnapshot::Weighted { children, .. } => {
+                children.iter().map(|c| c.child).collect()
+            }
+            GeneratorSnapshot::Repeat { child, .. }
+            | GeneratorSnapshot::RandomRepeat { child, .. } => {
+                vec![*child]
+            }
+            GeneratorSnapshot::StringLiteral {
+                child,
+                interpolation,
+                ..
+            } => std::iter::once(*child).chain(*interpolation).collect(),
+            GeneratorSnapshot::Expression {
+                operands, members, ..
+            } => operands.iter().copied().chain(*members).collect(),
+            GeneratorSnapshot::Block { header, body, .. } => vec![*header, *body],
+            GeneratorSnapshot::BalancedNesting { content, .. } => content.iter().copied().collect(),
+            GeneratorSnapshot::Script { generators, .. }
+            | GeneratorSnapshot::Template { generators, .. } => generators.clone(),
+        }
+    }
+
+    /// Creates the generator from the snapshot and the children it refers to, and checks it.
+    pub fn build(
+        &self,
+        mut children: Vec<Arc<dyn TypingPatternGenerator>>,
+    ) -> Result<Arc<dyn TypingPatternGenerator>, GeneratorError> {
+        let generator: Arc<dyn TypingPatternGenerator> = match self.clone() {
+            GeneratorSnapshot::SingleString { name, pattern } => {
+                Arc::new(SingleStringGenerator { name, pattern })
+            }
+            GeneratorSnapshot::ListOfPatterns {
+                name, delimiter, ..
+            } => Arc::new(ListOfPatternsGenerator {
+                name,
+                patterns: children,
+                delimiter,
+            }),
+            GeneratorSnapshot::OneOfStrings { name, strings } => {
+                Arc::new(OneOfStringsPatternGenerator { name, strings })
+            }
+            GeneratorSnapshot::Weighted {
+                name,
+                children: weights,
+            } => {
+                let patterns: Vec<(f32, Arc<dyn TypingPatternGenerator>)> =
+                    weights.iter().map(|c| c.weight).zip(children).collect();
+                Arc::new(WeightedPatternGenerator {
+                    name,
+                    total_weight: patterns.iter().map(|x| x.0).sum(),
+                    patterns,
+                })
+            }
+            GeneratorSnapshot::Repeat {
+                name,
+                count,
+                delimiter,
+                camel_case_strings,
+                ..
+            } => Arc::new(RepeatPatternGenerator {
+                name,
+                pattern: children.remove(0),
+                count,
+                delimiter,
+                camel_case_strings,
+            }),
+            GeneratorSnapshot::RandomRepeat {
+                name,
+                min_count,
+                max_count,
+                delimiter,
+                camel_case_strings,
+                ..
+            } => Arc::new(RandomRepeatGenerator {
+                name,
+                pattern: children.remove(0),
+                min_count,
+                max_count,
+                delimiter,
+                camel_case_strings,
+            }),
+            GeneratorSnapshot::Number {
+                name,
+                format,
+                min_length,
+                max_length,
+                range,
+                leading_zeros,
+                uppercase,
+                negative_probability,
+                min_fraction_length,
+                max_fraction_length,
+                min_exponent,
+                max_exponent,
+                digit_separator,
+                separator_group,
+                suffixes,
+            } => Arc::new(NumberPatternGenerator {
+                name,
+                format,
+                min_length,
+                max_length,
+                range,
+                leading_zeros,
+                uppercase,
+                negative_probability,
+                min_fraction_length,
+                max_fraction_length,
+                min_exponent,
+                max_exponent,
+                digit_separator,
+                separator_group,
+                suffixes,
+            }),
+            GeneratorSnapshot::StringLiteral {
+                name,
+                styles,
+                escape_probability,
+                interpolation_probability,
+                ..
+            } => Arc::new(StringLiteralGenerator {
+                name,
+                pattern: children.remove(0),
+                interpolation: children.pop(),
+                styles,
+                escape_probability,
+                interpolation_probability,
+            }),
+            GeneratorSnapshot::Expression {
+                name,
+                operands,
+                members,
+                operators,
+                min_depth,
+                max_depth,
+                always_parenthesize,
+            } => {
+                let operators = operators
+                    .iter()
+                    .map(|o| {
+                        find_operator(&o.symbol, o.arity).ok_or_else(|| {
+                            GeneratorError::InvalidGraph {
+                                generator: name.clone(),
+                                reason: format!("unknown {:?} operator {}", o.arity, o.symbol),
+                            }
+                        })
+                    })
+                    .collect::<Result<Vec<_>, GeneratorError>>()?;
+                let members = members.and_then(|_| children.pop());
+                children.truncate(operands.len());
+                Arc::new(ExpressionGenerator {
+                    name,
+                    operands: children,
+                    members,
+                    operators,
+                    min_depth,
+                    max_depth,
+                    always_parenthesize,
+                })
+            }
+            GeneratorSnapshot::Block {
+                name,
+                min_lines,
+                max_lines,
+                open,
+                close,
+                ..
+            } => Arc::new(BlockGenerator {
+                name,
+                header: children.remove(0),
+                body: children.remove(0),
+                min_lines,
+                max_lines,
+                open,
+                close,
+            }),
+            GeneratorSnapshot::BalancedNesting {
+                name,
+                pairs,
+                min_depth,
+                max_depth,
+                max_siblings,
+                content_probability,
+                delimiter,
+                closing_runs,
+                terminator,
+                terminator_probability,
+                ..
+            } => Arc::new(BalancedNestingGenerator {
+                name,
+                content: children.pop(),
+                pairs,
+                min_depth,
+                max_depth,
+                max_siblings,
+                content_probability,
+                delimiter,
+                closing_runs,
+                terminator,
+                terminator_probability,
+            }),
+            GeneratorSnapshot::Script {
+                name,
+                script,
+                max_operations,
+                ..
+            } => Arc::new(ScriptGenerator::from_script(
+                &name,
+                script,
+                children,
+                max_operations,
+            )?),
+            GeneratorSnapshot::Template { name, template, .. } => {
+                Arc::new(TemplateGenerator::from_template(&name, template, children)?)
+            }
+        };
+        generator.check()?;
+        Ok(generator)
+    }
+}
@@ -0,0 +1,708 @@
+use crate::generators::error::GeneratorError;
+use crate::generators::helpers::{
+    check_options, check_probability, check_range, config_probability, config_range, config_value,
+};
+use crate::generators::random::{self, GeneratorRng};
+use crate::generators::{TypingPattern, TypingPatternGenerator};
+use rand::prelude::SliceRandom;
+use rand::Rng;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+fn hex_digits(rng: &mut GeneratorRng, count: usize, uppercase: bool) -> String {
+    (0..count)
+        .map(|_| {
+            let c = char::from_digit(rng.gen_range(0..16), 16).unwrap();
+            if uppercase {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateTimeKind {
+    Date,
+    Time,
+    DateTime,
+}
+
+impl FromStr for DateTimeKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "date" => Ok(DateTimeKind::Date),
+            "time" => Ok(DateTimeKind::Time),
+            "datetime" => Ok(DateTimeKind::DateTime),
+            _ => Err(String::from("expected date, time or datetime")),
+        }
+    }
+}
+
+impl fmt::Display for DateTimeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            DateTimeKind::Date => "date",
+            DateTimeKind::Time => "time",
+            DateTimeKind::DateTime => "datetime",
+        };
+        write!(f, "{}", kind)
+    }
+}
+
+/// Generates ISO 8601 dates (`2023-04-17`), times (`13:05:59`) or both (`2023-04-17T13:05:59.123+02:00`).
+///
+/// `kind` is one of `date`, `time` or `datetime`. Fractional seconds and time zones
+/// are added with `fraction_probability` and `timezone_probability`.
+#[derive(Debug)]
+pub struct DateTimePatternGenerator {
+    pub name: String,
+    pub kind: DateTimeKind,
+    pub min_year: u32,
+    pub max_year: u32,
+    pub fraction_probability: f64,
+    pub timezone_probability: f64,
+}
+
+impl DateTimePatternGenerator {
+    pub fn new(name: &'static str, config: HashMap<&str, String>) -> Result<Self, GeneratorError> {
+        check_options(
+            name,
+            &config,
+            &[
+                "kind",
+                "min_year",
+                "max_year",
+                "fraction_probability",
+                "timezone_probability",
+            ],
+        )?;
+        let kind = config_value(name, &config, "kind", DateTimeKind::DateTime)?;
+        let (min_year, max_year) =
+            config_range(name, &config, ("min_year", "max_year"), (1970, 2038))?;
+        Ok(DateTimePatternGenerator {
+            name: name.to_string(),
+            kind,
+            min_year,
+            max_year,
+            fraction_probability: config_probability(name, &config, "fraction_probability", 0.3)?,
+            timezone_probability: config_probability(name, &config, "timezone_probability", 0.5)?,
+        })
+    }
+
+    fn date(&self, rng: &mut GeneratorRng) -> String {
+        let year = rng.gen_range(self.min_year..=self.max_year);
+        let month = rng.gen_range(1..=12);
+        let days = match month {
+            2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        };
+        format!("{:04}-{:02}-{:02}", year, month, rng.gen_range(1..=days))
+    }
+
+    fn time(&self, rng: &mut GeneratorRng) -> String {
+        let mut time = format!(
+            "{:02}:{:02}:{:02}",
+            rng.gen_range(0..24),
+            rng.gen_range(0..60),
+            rng.gen_range(0..60)
+        );
+        if rng.gen_bool(self.fraction_probability) {
+            time.push_str(&format!(".{:03}", rng.gen_range(0..1000)));
+        }
+        if rng.gen_bool(self.timezone_probability) {
+            if rng.gen_bool(0.5) {
+                time.push('Z');
+            } else {
+                let sign = if rng.gen_bool(0.5) { '+' } else { '-' };
+                let minutes = [0, 30, 45].choose(rng).unwrap();
+                time.push_str(&format!(
+                    "{}{:02}:{:02}",
+                    sign,
+                    rng.gen_range(0..13),
+                    minutes
+                ));
+            }
+        }
+        time
+    }
+}
+
+impl TypingPatternGenerator for DateTimePatternGenerator {
+    fn generate(&self) -> TypingPattern {
+        let mut rng = random::rng();
+        let pattern = match self.kind {
+            DateTimeKind::Date => self.date(&mut rng),
+            DateTimeKind::Time => self.time(&mut rng),
+            DateTimeKind::DateTime => format!("{}T{}", self.date(&mut rng), self.time(&mut rng)),
+        };
+        TypingPattern {
+            name: self.name.clone(),
+            pattern,
+            children: Vec::new(),
+        }
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("kind", self.kind.to_string()),
+            ("min_year", self.min_year.to_string()),
+            ("max_year", self.max_year.to_string()),
+            (
+                "fraction_probability",
+                self.fraction_probability.to_string(),
+            ),
+            (
+                "timezone_probability",
+                self.timezone_probability.to_string(),
+            ),
+        ]
+    }
+
+    fn check(&self) -> Result<(), GeneratorError> {
+        check_range(
+            &self.name,
+            ("min_year", "max_year"),
+            &self.min_year,
+            &self.max_year,
+        )?;
+        check_probability(
+            &self.name,
+            "fraction_probability",
+            self.fraction_probability,
+        )?;
+        check_probability(
+            &self.name,
+            "timezone_probability",
+            self.timezone_probability,
+        )
+    }
+}
+
+/// Generates IPv4 (`192.168.1.20`) and IPv6 (`fe80::1c2:3d4`) addresses.
+///
+/// `ipv6_probability` picks between the two. CIDR prefixes (`/24`) and ports (`:8080`, `[::1]:8080`)
+/// are added with `prefix_probability` and `port_probability`.
+#[derive(Debug)]
+pub struct IpAddressPatternGenerator {
+    pub name: String,
+    pub ipv6_probability: f64,
+    pub compress_probability: f64,
+    pub prefix_probability: f64,
+    pub port_probability: f64,
+    pub uppercase: bool,
+}
+
+impl IpAddressPatternGenerator {
+    pub fn new(name: &'static str, config: HashMap<&str, String>) -> Result<Self, GeneratorError> {
+        check_options(
+            name,
+            &config,
+            &[
+                "ipv6_probability",
+                "compress_probability",
+                "prefix_probability",
+                "port_probability",
+                "uppercase",
+            ],
+        )?;
+        Ok(IpAddressPatternGenerator {
+            name: name.to_string(),
+            ipv6_probability: config_probability(name, &config, "ipv6_probability", 0.3)?,
+            compress_probability: config_probability(name, &config, "compress_probability", 0.7)?,
+            prefix_probability: config_probability(name, &config, "prefix_probability", 0.0)?,
+            port_probability: config_probability(name, &config, "port_probability", 0.0)?,
+            uppercase: config_value(name, &config, "uppercase", false)?,
+        })
+    }
+
+    fn ipv6(&self, rng: &mut GeneratorRng) -> String {
+        let groups: Vec<String> = (0..8)
+            .map(|_| {
+                // Groups drop their leading zeros, and zero groups are common.
+                if rng.gen_bool(0.3) {
+                    String::from("0")
+                } else {
+                    let group = hex_digits(rng, 4, self.uppercase);
+                    let trimmed = group.trim_start_matches('0');
+                    if trimmed.is_empty() {
+                        String::from("0")
+                    } else {
+                        trimmed.to_string()
+                    }
+                }
+            })
+            .collect();
+        if !rng.gen_bool(self.compress_probability) {
+            return groups.join(":");
+        }
+        // Replace a random run of groups with `::`.
+        let start = rng.gen_range(0..8);
+        let end = rng.gen_range(start + 1..=8);
+        format!("{}::{}", groups[..start].join(":"), groups[end..].join(":"))
+    }
+}
+
+impl TypingPatternGenerator for IpAddressPatternGenerator {
+    fn generate(&self) -> TypingPattern {
+        let mut rng = random::rng();
+        let ipv6 = rng.gen_bool(self.ipv6_probability);
+        let mut pattern = if ipv6 {
+            self.ipv6(&mut rng)
+        } else {
+            (0..4)
+                .map(|_| rng.gen_range(0..=255).to_string())
+                .collect::<Vec<String>>()
+                .join(".")
+        };
+        if rng.gen_bool(self.prefix_probability) {
+            let max_prefix = if ipv6 { 128 } else { 32 };
+            pattern.push_str(&format!("/{}", rng.gen_range(8..=max_prefix)));
+        } else if rng.gen_bool(self.port_probability) {
+            if ipv6 {
+                pattern = format!("[{}]", pattern);
+            }
+            pattern.push_str(&format!(":{}", rng.gen_range(1..=65535)));
+        }
+        TypingPattern {
+            name: self.name.clone(),
+            pattern,
+            children: Vec::new(),
+        }
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("ipv6_probability", self.ipv6_probability.to_string()),
+            (
+                "compress_probability",
+                self.compress_probability.to_string(),
+            ),
+            ("prefix_probability", self.prefix_probability.to_string()),
+            ("port_probability", self.port_probability.to_string()),
+            ("uppercase", self.uppercase.to_string()),
+        ]
+    }
+
+    fn check(&self) -> Result<(), GeneratorError> {
+        check_probability(&self.name, "ipv6_probability", self.ipv6_probability)?;
+        check_probability(
+            &self.name,
+            "compress_probability",
+            self.compress_probability,
+        )?;
+        check_probability(&self.name, "prefix_probability", self.prefix_probability)?;
+        check_probability(&self.name, "port_probability", self.port_probability)
+    }
+}
+
+/// Generates random version 4 UUIDs, e.g. `3f2b8c1e-9a4d-4f6b-b2e1-7c9d0a5e4f21`.
+#[derive(Debug)]
+pub struct UuidPatternGenerator {
+    pub name: String,
+    pub uppercase: bool,
+}
+
+impl UuidPatternGenerator {
+    pub fn new(name: &'static str, config: HashMap<&str, String>) -> Result<Self, GeneratorError> {
+        check_options(name, &config, &["uppercase"])?;
+        Ok(UuidPatternGenerator {
+            name: name.to_string(),
+            uppercase: config_value(name, &config, "uppercase", false)?,
+        })
+    }
+}
+
+impl TypingPatternGenerator for UuidPatternGenerator {
+    fn generate(&self) -> TypingPattern {
+        let mut rng = random::rng();
+        let variant = ['8', '9', 'a', 'b'].choose(&mut rng).unwrap();
+        let variant = if self.uppercase {
+            variant.to_ascii_uppercase()
+        } else {
+            *variant
+        };
+        let pattern = format!(
+            "{}-{}-4{}-{}{}-{}",
+            hex_digits(&mut rng, 8, self.uppercase),
+            hex_digits(&mut rng, 4, self.uppercase),
+            hex_digits(&mut rng, 3, self.uppercase),
+            variant,
+            hex_digits(&mut rng, 3, self.uppercase),
+            hex_digits(&mut rng, 12, self.uppercase)
+        );
+        TypingPattern {
+            name: self.name.clone(),
+            pattern,
+            children: Vec::new(),
+        }
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        vec![("uppercase", self.uppercase.to_string())]
+    }
+}
+
+/// Generates CSS hex colors: `#a1b2c3`, and with `short_probability` and `alpha_probability`
+/// also `#abc` and `#a1b2c3ff`.
+#[derive(Debug)]
+pub struct HexColorPatternGenerator {
+    pub name: String,
+    pub short_probability: f64,
+    pub alpha_probability: f64,
+    pub uppercase: bool,
+}
+
+impl HexColorPatternGenerator {
+    pub fn new(name: &'static str, config: HashMap<&str, String>) -> Result<Self, GeneratorError> {
+        check_options(
+            name,
+            &config,
+            &["short_probability", "alpha_probability", "uppercase"],
+        )?;
+        Ok(HexColorPatternGenerator {
+            name: name.to_string(),
+            short_probability: config_probability(name, &config, "short_probability", 0.2)?,
+            alpha_probability: config_probability(name, &config, "alpha_probability", 0.1)?,
+            uppercase: config_value(name, &config, "uppercase", false)?,
+        })
+    }
+}
+
+impl TypingPatternGenerator for HexColorPatternGenerator {
+    fn generate(&self) -> TypingPattern {
+        let mut rng = random::rng();
+        let mut digits = if rng.gen_bool(self.short_probability) {
+            3
+        } else {
+            6
+        };
+        if rng.gen_bool(self.alpha_probability) {
+            digits += digits / 3;
+        }
+        TypingPattern {
+            name: self.name.clone(),
+            pattern: format!("#{}", hex_digits(&mut rng, digits, self.uppercase)),
+            children: Vec::new(),
+        }
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("short_probability", self.short_probability.to_string()),
+            ("alpha_probability", self.alpha_probability.to_string()),
+            ("uppercase", self.uppercase.to_string()),
+        ]
+    }
+
+    fn check(&self) -> Result<(), GeneratorError> {
+        check_probability(&self.name, "short_probability", self.short_probability)?;
+        check_probability(&self.name, "alpha_probability", self.alpha_probability)
+    }
+}
+
+/// Generates semantic versions like `1.4.12`, `0.3.0-rc.2+build.7` or, as dependency
+/// requirements, `^2.1.0` and `>=0.8.5`.
+#[derive(Debug)]
+pub struct SemverPatternGenerator {
+    pub name: String,
+    pub max_component: u32,
+    pub pre_release_probability: f64,
+    pub build_probability: f64,
+    pub requirement_probability: f64,
+}
+
+impl SemverPatternGenerator {
+    pub fn new(name: &'static str, config: HashMap<&str, String>) -> Result<Self, GeneratorError> {
+        check_options(
+            name,
+            &config,
+            &[
+                "max_component",
+                "pre_release_probability",
+                "build_probability",
+                "requirement_probability",
+            ],
+        )?;
+        Ok(SemverPatternGenerator {
+            name: name.to_string(),
+            max_component: config_value(name, &config, "max_component", 20)?,
+            pre_release_probability: config_probability(
+                name,
+                &config,
+                "pre_release_probability",
+                0.2,
+            )?,
+            build_probability: config_probability(name, &config, "build_probability", 0.1)?,
+            requirement_probability: config_probability(
+                name,
+                &config,
+                "requirement_probability",
+                0.0,
+            )?,
+        })
+    }
+}
+
+impl TypingPatternGenerator for SemverPatternGenerator {
+    fn generate(&self) -> TypingPattern {
+        let mut rng = random::rng();
+        let mut pattern = String::new();
+        if rng.gen_bool(self.requirement_probability) {
+            pattern.push_str(["^", "~", ">=", "<", "="].choose(&mut rng).unwrap());
+        }
+        pattern.push_str(&format!(
+            "{}.{}.{}",
+            rng.gen_range(0..=self.max_component),
+            rng.gen_range(0..=self.max_component),
+            rng.gen_range(0..=self.max_component)
+        ));
+        if rng.gen_bool(self.pre_release_probability) {
+            let label = ["alpha", "beta", "rc", "pre", "dev"]
+                .choose(&mut rng)
+                .unwrap();
+            pattern.push_str(&format!("-{}.{}", label, rng.gen_range(0..10)));
+        }
+        if rng.gen_bool(self.build_probability) {
+            pattern.push_str(&format!("+build.{}", rng.gen_range(1..1000)));
+        }
+        TypingPattern {
+            name: self.name.clone(),
+            pattern,
+            children: Vec::new(),
+        }
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("max_component", self.max_component.to_string()),
+            (
+                "pre_release_probability",
+                self.pre_release_probability.to_string(),
+            ),
+            ("build_probability", self.build_probability.to_string()),
+            (
+                "requirement_probability",
+                self.requirement_probability.to_string(),
+            ),
+        ]
+    }
+
+    fn check(&self) -> Result<(), GeneratorError> {
+        check_probability(
+            &self.name,
+            "pre_release_probability",
+            self.pre_release_probability,
+        )?;
+        check_probability(&self.name, "build_probability", self.build_probability)?;
+        check_probability(
+            &self.name,
+            "requirement_probability",
+            self.requirement_probability,
+        )
+    }
+}
+
+/// Generates durations such as `250ms`, `30s` or `1h30m`, using the comma-separated `units`.
+///
+/// With `compound_probability` a second, smaller unit is appended, as Go and humantime accept.
+#[derive(Debug)]
+pub struct DurationPatternGenerator {
+    pub name: String,
+    pub units: Vec<String>,
+    pub min_value: u32,
+    pub max_value: u32,
+    pub compound_probability: f64,
+}
+
+impl DurationPatternGenerator {
+    pub fn new(name: &'static str, config: HashMap<&str, String>) -> Result<Self, GeneratorError> {
+        check_options(
+            name,
+            &config,
+            &["units", "min_value", "max_value", "compound_probability"],
+        )?;
+        let units: Vec<String> =
+            config_value(name, &config, "units", String::from("ns,us,ms,s,m,h"))?
+                .split(',')
+                .map(|x| x.trim().to_string())
+                .filter(|x| !x.is_empty())
+                .collect();
+        if units.is_empty() {
+            return Err(GeneratorError::NoChoices {
+                generator: name.to_string(),
+                reason: String::from("units is empty"),
+            });
+        }
+        let (min_value, max_value) =
+            config_range(name, &config, ("min_value", "max_value"), (1, 500))?;
+        Ok(DurationPatternGenerator {
+            name: name.to_string(),
+            units,
+            min_value,
+            max_value,
+            compound_probability: config_probability(name, &config, "compound_probability", 0.1)?,
+        })
+    }
+}
+
+impl TypingPatternGenerator for DurationPatternGenerator {
+    fn generate(&self) -> TypingPattern {
+        let mut rng = random::rng();
+        let index = rng.gen_range(0..self.units.len());
+        let mut pattern = format!(
+            "{}{}",
+            rng.gen_range(self.min_value..=self.max_value),
+            self.units[index]
+        );
+        if index > 0 && rng.gen_bool(self.compound_probability) {
+            let smaller = rng.gen_range(0..index);
+            pattern.push_str(&format!("{}{}", rng.gen_range(1..60), self.units[smaller]));
+        }
+        TypingPattern {
+            name: self.name.clone(),
+            pattern,
+            children: Vec::new(),
+        }
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("units", self.units.join(",")),
+            ("min_value", self.min_value.to_string()),
+            ("max_value", self.max_value.to_string()),
+            (
+                "compound_probability",
+                self.compound_probability.to_string(),
+            ),
+        ]
+    }
+
+    fn check(&self) -> Result<(), GeneratorError> {
+        if self.units.is_empty() {
+            return Err(GeneratorError::NoChoices {
+                generator: self.name.clone(),
+                reason: String::from("units is empty"),
+            });
+        }
+        check_range(
+            &self.name,
+            ("min_value", "max_value"),
+            &self.min_value,
+            &self.max_value,
+        )?;
+        check_probability(
+            &self.name,
+            "compound_probability",
+            self.compound_probability,
+        )
+    }
+}
+
+const WELL_KNOWN_PORTS: [u16; 14] = [
+    22, 25, 53, 80, 443, 3000, 3306, 5432, 6379, 8000, 8080, 8443, 9000, 27017,
+];
+const HOSTS: [&str; 4] = ["localhost", "127.0.0.1", "0.0.0.0", "example.com"];
+
+/// Generates port numbers, picking a common one like `8080` with `well_known_probability`
+/// and prefixing a host like `localhost:` with `host_probability`.
+#[derive(Debug)]
+pub struct PortPatternGenerator {
+    pub name: String,
+    pub well_known_probability: f64,
+    pub host_probability: f64,
+}
+
+impl PortPatternGenerator {
+    pub fn new(name: &'static str, config: HashMap<&str, String>) -> Result<Self, GeneratorError> {
+        check_options(
+            name,
+            &config,
+            &["well_known_probability", "host_probability"],
+        )?;
+        Ok(PortPatternGenerator {
+            name: name.to_string(),
+            well_known_probability: config_probability(
+                name,
+                &config,
+                "well_known_probability",
+                0.5,
+            )?,
+            host_probability: config_probability(name, &config, "host_probability", 0.0)?,
+        })
+    }
+}
+
+impl TypingPatternGenerator for PortPatternGenerator {
+    fn generate(&self) -> TypingPattern {
+        let mut rng = random::rng();
+        let port = if rng.gen_bool(self.well_known_probability) {
+            *WELL_KNOWN_PORTS.choose(&mut rng).unwrap()
+        } else {
+            rng.gen_range(1024..=65535)
+        };
+        let pattern = if rng.gen_bool(self.host_probability) {
+            format!("{}:{}", HOSTS.choose(&mut rng).unwrap(), port)
+        } else {
+            port.to_string()
+        };
+        TypingPattern {
+            name: self.name.clone(),
+            pattern,
+            children: Vec::new(),
+        }
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        vec![
+            (
+                "well_known_probability",
+                self.well_known_probability.to_string(),
+            ),
+            ("host_probability", self.host_probability.to_string()),
+        ]
+    }
+
+    fn check(&self) -> Result<(), GeneratorError> {
+        check_probability(
+            &self.name,
+            "well_known_probability",
+            self.well_known_probability,
+        )?;
+        check_probability(&self.name, "host_probability", self.host_probability)
+    }
+}
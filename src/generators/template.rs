@@ -0,0 +1,481 @@
+use crate::generators::enumeration::repeat_cardinality;
+use crate::generators::error::GeneratorError;
+use crate::generators::helpers::{check_options, required_config_value, uppercase_first_letter};
+use crate::generators::random;
+use crate::generators::snapshot::GeneratorSnapshot;
+use crate::generators::{TypingPattern, TypingPatternGenerator};
+use rand::Rng;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// How a placeholder changes the case of its pattern. Words are separated by spaces, `_`, `-`
+/// and changes to upper case, so `fooBar`, `foo_bar` and `foo bar` all become `FooBar` in
+/// `Pascal` case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Case {
+    Lower,
+    Upper,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    Camel,
+    Pascal,
+}
+
+const CASES: [(&str, Case); 7] = [
+    ("lower", Case::Lower),
+    ("upper", Case::Upper),
+    ("snake", Case::Snake),
+    ("screaming_snake", Case::ScreamingSnake),
+    ("kebab", Case::Kebab),
+    ("camel", Case::Camel),
+    ("pascal", Case::Pascal),
+];
+
+impl FromStr for Case {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CASES
+            .iter()
+            .find(|(name, _)| *name == s)
+            .map(|(_, case)| *case)
+            .ok_or_else(|| {
+                let names: Vec<&str> = CASES.iter().map(|(name, _)| *name).collect();
+                format!(
+                    "unknown modifier {:?}, expected one of {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+impl Case {
+    pub fn apply(self, s: &str) -> String {
+        let words = split_words(s);
+        let lower = || words.iter().map(|w| w.to_lowercase());
+        match self {
+            Case::Lower => s.to_lowercase(),
+            Case::Upper => s.to_uppercase(),
+            Case::Snake => lower().collect::<Vec<_>>().join("_"),
+            Case::ScreamingSnake => words
+                .iter()
+                .map(|w| w.to_uppercase())
+                .collect::<Vec<_>>()
+                .join("_"),
+            Case::Kebab => lower().collect::<Vec<_>>().join("-"),
+            Case::Camel => lower()
+                .enumerate()
+                .map(|(i, w)| {
+                    if i == 0 {
+                        w
+                    } else {
+                        uppercase_first_letter(&w)
+                    }
+                })
+                .collect(),
+            Case::Pascal => lower().map(|w| uppercase_first_letter(&w)).collect(),
+        }
+    }
+}
+
+fn split_words(s: &str) -> Vec<String> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if matches!(c, ' ' | '_' | '-') {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        // A new word starts at `B` in `aB` and `1B`, and at `S` in `HTTPServer`.
+        let boundary = c.is_uppercase()
+            && i > 0
+            && (chars[i - 1].is_lowercase()
+                || chars[i - 1].is_ascii_digit()
+                || (chars[i - 1].is_uppercase()
+                    && chars.get(i + 1).is_some_and(|next| next.is_lowercase())));
+        if boundary && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+#[derive(Debug)]
+struct Placeholder {
+    /// Index into the generators of the template.
+    generator: usize,
+    /// A copy of the generator with the range of the placeholder.
+    ranged: Option<Arc<dyn TypingPatternGenerator>>,
+    min_count: u32,
+    /// Inclusive.
+    max_count: u32,
+    cases: Vec<Case>,
+}
+
+#[derive(Debug)]
+enum Part {
+    Literal(String),
+    Placeholder(Placeholder),
+}
+
+/// Fills the placeholders of a template with patterns of generators found by name, e.g.
+/// `{symbols}[{number}].{symbols}({arguments})`. A placeholder is written
+/// `{name[:range][*count][|case]...}`:
+///
+/// - `name`: the generator, which has to be the only one of that name
+/// - `:4`, `:2..4` or `:2..=4`: the range of the generator for this placeholder, see
+///   `TypingPatternGenerator::with_range`. So `{number:2..4}` is a number of two or three
+///   digits and `{symbols:1..=3}` joins one to three symbols, if `symbols` repeats randomly.
+/// - `*3`, `*1..3` or `*1..=3`: generate that many patterns, joined by spaces
+/// - `|snake`: one of the `Case` modifiers `lower`, `upper`, `snake`, `screaming_snake`,
+///   `kebab`, `camel` and `pascal`, applied to the whole placeholder. They can be chained and
+///   apply from left to right.
+///
+/// So `{number:1..=3*2..=4|upper}` is two to four numbers of up to three digits. `{{` and `}}`
+/// stand for literal braces.
+#[derive(Debug)]
+pub struct TemplateGenerator {
+    pub name: String,
+    pub template: String,
+    /// The generators the template refers to, in the order of their first placeholder.
+    pub generators: Vec<Arc<dyn TypingPatternGenerator>>,
+    parts: Vec<Part>,
+}
+
+impl TemplateGenerator {
+    /// Looks the placeholders up in `generators` and keeps only the generators they refer to,
+    /// so any list of generators, e.g. all of a graph, can serve as the registry.
+    pub fn new(
+        name: &'static str,
+        generators: Vec<Arc<dyn TypingPatternGenerator>>,
+        config: HashMap<&str, String>,
+    ) -> Result<Self, GeneratorError> {
+        check_options(name, &config, &["template"])?;
+        let template = required_config_value(name, &config, "template")?;
+        TemplateGenerator::from_template(name, template, generators)
+    }
+
+    pub fn from_template(
+        name: &str,
+        template: String,
+        generators: Vec<Arc<dyn TypingPatternGenerator>>,
+    ) -> Result<Self, GeneratorError> {
+        let mut parts = parse(name, &template, &generators)?;
+        // Renumber the placeholders to index the generators that are used.
+        let mut used: Vec<usize> = Vec::new();
+        for part in &mut parts {
+            if let Part::Placeholder(placeholder) = part {
+                placeholder.generator = match used.iter().position(|&i| i == placeholder.generator)
+                {
+                    Some(i) => i,
+                    None => {
+                        used.push(placeholder.generator);
+                        used.len() - 1
+                    }
+                };
+            }
+        }
+        Ok(TemplateGenerator {
+            name: name.to_string(),
+            template,
+            generators: used.iter().map(|&i| generators[i].clone()).collect(),
+            parts,
+        })
+    }
+
+    /// Writes the pattern of a placeholder, recording the patterns of its generator in
+    /// `children` if given.
+    fn fill(
+        &self,
+        placeholder: &Placeholder,
+        pattern: &mut String,
+        mut children: Option<&mut Vec<TypingPattern>>,
+    ) {
+        let count = random::rng().gen_range(placeholder.min_count..=placeholder.max_count);
+        let start = pattern.len();
+        let generator = placeholder
+            .ranged
+            .as_ref()
+            .unwrap_or(&self.generators[placeholder.generator]);
+        for i in 0..count {
+            if i > 0 {
+                pattern.push(' ');
+            }
+            match children.as_deref_mut() {
+                Some(children) => {
+                    let child = generator.generate();
+                    pattern.push_str(&child.pattern);
+                    children.push(child);
+                }
+                None => generator.generate_into(pattern),
+            }
+        }
+        if !placeholder.cases.is_empty() {
+            let text = placeholder
+                .cases
+                .iter()
+                .fold(pattern.split_off(start), |text, case| case.apply(&text));
+            pattern.push_str(&text);
+        }
+    }
+}
+
+fn parse(
+    name: &str,
+    template: &str,
+    generators: &[Arc<dyn TypingPatternGenerator>],
+) -> Result<Vec<Part>, GeneratorError> {
+    let invalid = |reason: String| GeneratorError::InvalidOption {
+        generator: name.to_string(),
+        option: String::from("template"),
+        value: template.to_string(),
+        reason,
+    };
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '}' => return Err(invalid(String::from("unmatched }, write }} for a brace"))),
+            '{' => {
+                let mut spec = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some('{') | None => {
+                            return Err(invalid(String::from("unclosed {, write {{ for a brace")))
+                        }
+                        Some(c) => spec.push(c),
+                    }
+                }
+                if !literal.is_empty() {
+                    parts.push(Part::Literal(std::mem::take(&mut literal)));
+                }
+                parts.push(Part::Placeholder(
+                    parse_placeholder(&spec, generators).map_err(invalid)?,
+                ));
+            }
+            c => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        parts.push(Part::Literal(literal));
+    }
+    Ok(parts)
+}
+
+/// Parses `name[:range][*count][|case]...`.
+fn parse_placeholder(
+    spec: &str,
+    generators: &[Arc<dyn TypingPatternGenerator>],
+) -> Result<Placeholder, String> {
+    let mut modifiers = spec.split('|');
+    let head = modifiers.next().unwrap_or_default();
+    let (name, count) = match head.split_once('*') {
+        Some((name, count)) => (name.trim(), Some(count.trim())),
+        None => (head.trim(), None),
+    };
+    if name.is_empty() {
+        return Err(format!(
+            "the placeholder {{{}}} has no generator name",
+            spec
+        ));
+    }
+    let (name, range) = match name.split_once(':') {
+        Some((name, range)) => (name.trim(), Some(range.trim())),
+        None => (name, None),
+    };
+    let generator = find_generator(name, generators)?;
+    let ranged = match range {
+        Some(range) => {
+            let (min, max) = parse_range(range)?;
+            let ranged = generators[generator]
+                .with_range(min, max)
+                .ok_or_else(|| format!("{:?} has no range to set", name))?;
+            ranged
+                .check()
+                .map_err(|e| format!("{:?} with the range {}: {}", name, range, e))?;
+            Some(ranged)
+        }
+        None => None,
+    };
+    let (min_count, max_count) = match count {
+        Some(count) => parse_range(count)?,
+        None => (1, 1),
+    };
+    let cases = modifiers
+        .map(|case| case.trim().parse())
+        .collect::<Result<Vec<Case>, String>>()?;
+    Ok(Placeholder {
+        generator,
+        ranged,
+        min_count,
+        max_count,
+        cases,
+    })
+}
+
+/// The index of the generator named `name`. The same generator can be listed more than once,
+/// e.g. when the registry is a graph's nodes and its named generators, but different ones with
+/// the same name are ambiguous.
+fn find_generator(
+    name: &str,
+    generators: &[Arc<dyn TypingPatternGenerator>],
+) -> Result<usize, String> {
+    let mut found: Option<usize> = None;
+    for (i, generator) in generators.iter().enumerate() {
+        if generator.name() != name {
+            continue;
+        }
+        match found {
+            None => found = Some(i),
+            Some(first) if !Arc::ptr_eq(&generators[first], generator) => {
+                return Err(format!("more than one generator is named {:?}", name))
+            }
+            Some(_) => {}
+        }
+    }
+    found.ok_or_else(|| format!("there is no generator named {:?}", name))
+}
+
+/// Parses `n`, `min..max` or `min..=max` into an inclusive range.
+fn parse_range(range: &str) -> Result<(u32, u32), String> {
+    let number = |s: &str| {
+        s.trim()
+            .parse::<u32>()
+            .map_err(|_| format!("{:?} is not a number or a range like 2..4", range))
+    };
+    let empty = || format!("{:?} is an empty range", range);
+    if let Some((min, max)) = range.split_once("..=") {
+        let (min, max) = (number(min)?, number(max)?);
+        if min > max {
+            return Err(empty());
+        }
+        Ok((min, max))
+    } else if let Some((min, max)) = range.split_once("..") {
+        let (min, max) = (number(min)?, number(max)?);
+        if min >= max {
+            return Err(empty());
+        }
+        Ok((min, max - 1))
+    } else {
+        let n = number(range)?;
+        Ok((n, n))
+    }
+}
+
+impl TypingPatternGenerator for TemplateGenerator {
+    fn generate(&self) -> TypingPattern {
+        let mut pattern = String::new();
+        let mut children = Vec::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => pattern.push_str(text),
+                Part::Placeholder(placeholder) => {
+                    self.fill(placeholder, &mut pattern, Some(&mut children))
+                }
+            }
+        }
+        TypingPattern {
+            name: self.name.clone(),
+            pattern,
+            children,
+        }
+    }
+
+    fn generate_into(&self, pattern: &mut String) {
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => pattern.push_str(text),
+                Part::Placeholder(placeholder) => self.fill(placeholder, pattern, None),
+            }
+        }
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn snapshot(&self, children: &[usize]) -> Option<GeneratorSnapshot> {
+        Some(GeneratorSnapshot::Template {
+            name: self.name.clone(),
+            template: self.template.clone(),
+            generators: children.to_vec(),
+        })
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        vec![("template", format!("{:?}", self.template))]
+    }
+
+    fn children(&self) -> Vec<Arc<dyn TypingPatternGenerator>> {
+        self.generators.clone()
+    }
+
+    fn min_length(&self, children: &[usize]) -> usize {
+        self.parts.iter().fold(0usize, |total, part| {
+            let length = match part {
+                Part::Literal(text) => text.chars().count(),
+                Part::Placeholder(placeholder) => {
+                    let count = placeholder.min_count as usize;
+                    let child = match &placeholder.ranged {
+                        // The bounds of the copy's children aren't known, so only a copy
+                        // without children has a better bound than nothing.
+                        Some(ranged) if ranged.children().is_empty() => ranged.min_length(&[]),
+                        Some(_) => 0,
+                        None => children[placeholder.generator],
+                    };
+                    match child {
+                        usize::MAX if count > 0 => usize::MAX,
+                        // Changing the case can drop the spaces and separators.
+                        _ if !placeholder.cases.is_empty() => 0,
+                        length => length
+                            .saturating_mul(count)
+                            .saturating_add(count.saturating_sub(1)),
+                    }
+                }
+            };
+            total.saturating_add(length)
+        })
+    }
+
+    fn check(&self) -> Result<(), GeneratorError> {
+        parse(&self.name, &self.template, &self.generators).map(|_| ())
+    }
+
+    fn cardinality(&self, children: &[Option<u128>]) -> Option<u128> {
+        self.parts.iter().try_fold(1u128, |total, part| {
+            let Part::Placeholder(placeholder) = part else {
+                return Some(total);
+            };
+            let child = match &placeholder.ranged {
+                Some(ranged) if ranged.children().is_empty() => ranged.cardinality(&[]),
+                Some(_) => None,
+                None => children[placeholder.generator],
+            };
+            let ways = (placeholder.min_count..=placeholder.max_count)
+                .try_fold(0u128, |sum, count| {
+                    sum.checked_add(repeat_cardinality(child, count)?)
+                })?;
+            total.checked_mul(ways)
+        })
+    }
+}
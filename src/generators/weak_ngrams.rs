@@ -0,0 +1,106 @@
+use crate::generators::error::GeneratorError;
+use crate::generators::helpers::{check_options, config_value};
+use crate::generators::random;
+use crate::generators::{TypingPattern, TypingPatternGenerator};
+use crate::session::ngrams::NgramStats;
+use rand::prelude::SliceRandom;
+use std::collections::HashMap;
+
+/// Generates drills dense in the n-grams a session log shows to be slowest or most error-prone,
+/// in the style of keybr.
+///
+/// Each item is a word containing one of the weak n-grams. N-grams that no word contains,
+/// which is common for symbol n-grams like `[0` or `);`, are drilled on their own.
+#[derive(Debug)]
+pub struct WeakNgramGenerator {
+    pub name: String,
+    /// The targeted n-grams, each with the words that contain it.
+    pub targets: Vec<(String, Vec<String>)>,
+    pub word_count: u32,
+    pub delimiter: String,
+}
+
+impl WeakNgramGenerator {
+    pub fn new(
+        name: &'static str,
+        stats: &NgramStats,
+        words: &[String],
+        config: HashMap<&str, String>,
+    ) -> Result<Self, GeneratorError> {
+        check_options(
+            name,
+            &config,
+            &["ngram_count", "min_samples", "word_count", "delimiter"],
+        )?;
+        let ngram_count = config_value(name, &config, "ngram_count", 5)?;
+        let min_samples = config_value(name, &config, "min_samples", 3)?;
+        let word_count = config_value(name, &config, "word_count", 6)?;
+        let delimiter = config_value(name, &config, "delimiter", String::from(" "))?;
+        let targets = stats
+            .weakest(ngram_count, min_samples)
+            .into_iter()
+            .map(|(ngram, _)| {
+                let containing = words
+                    .iter()
+                    .filter(|word| word.contains(ngram))
+                    .cloned()
+                    .collect();
+                (ngram.to_string(), containing)
+            })
+            .collect();
+        Ok(WeakNgramGenerator {
+            name: name.to_string(),
+            targets,
+            word_count,
+            delimiter,
+        })
+    }
+}
+
+impl TypingPatternGenerator for WeakNgramGenerator {
+    fn generate(&self) -> TypingPattern {
+        let mut rng = random::rng();
+        let mut generated_words: Vec<String> = Vec::new();
+        if !self.targets.is_empty() {
+            for _ in 0..self.word_count {
+                let (ngram, words) = self.targets.choose(&mut rng).unwrap();
+                generated_words.push(words.choose(&mut rng).unwrap_or(ngram).clone());
+            }
+        }
+
+        TypingPattern {
+            name: self.name.clone(),
+            pattern: generated_words.join(&self.delimiter),
+            children: Vec::new(),
+        }
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        vec![
+            (
+                "ngrams",
+                format!(
+                    "{:?}",
+                    self.targets
+                        .iter()
+                        .map(|(ngram, _)| ngram)
+                        .collect::<Vec<&String>>()
+                ),
+            ),
+            ("word_count", self.word_count.to_string()),
+            ("delimiter", format!("{:?}", self.delimiter)),
+        ]
+    }
+
+    fn min_length(&self, _children: &[usize]) -> usize {
+        if self.targets.is_empty() || self.word_count == 0 {
+            0
+        } else {
+            1
+        }
+    }
+}
@@ -1,44 +1,84 @@
-use hidapi::HidApi;
+use hidapi::{HidApi, HidResult};
+use std::fmt;
 
-pub fn test_hidapi() {
-    println!("Printing all available HID devices?");
-    match HidApi::new() {
-        Ok(api) => {
-            for device in api.device_list() {
-                println!("{:04x}:{:04x} {} {}", device.vendor_id(), device.product_id(), device.manufacturer_string().unwrap(), device.product_string().unwrap());
-            }
-            match api.open(0x3297, 0x1969) {
-                Ok(dev) => {
-                    println!("Found device");
-                    // once we open the device we should do the handshake to start receiving raw events
-                    // for testing, I did this with the oryx tool in the browser and then started receiving raw HID reports:
-                    // [6, 5, 9, 254, 0, 0, 0, 0, 5, 9, 1, 0, 5, 9, 0, 0, 122, 232, 0, 8, 56, 113, 0, 0, 186, 22, 0, 32, 186, 22, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
-                    // [7, 5, 9, 254, 97, 71, 0, 8, 0, 0, 0, 0, 186, 22, 0, 32, 122, 232, 0, 8, 56, 113, 0, 0, 234, 22, 0, 32, 186, 22, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
-                    // [6, 6, 11, 254, 0, 0, 0, 0, 6, 11, 1, 0, 6, 11, 0, 0, 122, 232, 0, 8, 1, 81, 0, 0, 88, 11, 0, 32, 186, 22, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
-                    // [5, 1, 254, 32, 96, 23, 0, 32, 24, 11, 0, 32, 221, 33, 0, 8, 0, 0, 0, 0, 241, 161, 0, 0, 1, 0, 0, 0, 241, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
-                    // [7, 6, 11, 254, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 122, 232, 0, 8, 1, 81, 0, 0, 88, 11, 0, 32, 186, 22, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
-                    // [5, 0, 254, 32, 96, 23, 0, 32, 24, 11, 0, 32, 221, 33, 0, 8, 2, 0, 0, 0, 241, 161, 0, 0, 0, 0, 0, 0, 241, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
-                    // [6, 6, 11, 254, 0, 0, 0, 0, 6, 11, 1, 0, 6, 11, 0, 0, 122, 232, 0, 8, 1, 81, 0, 0, 88, 11, 0, 32, 186, 22, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
-                    // [5, 1, 254, 32, 96, 23, 0, 32, 24, 11, 0, 32, 221, 33, 0, 8, 0, 0, 0, 0, 241, 161, 0, 0, 1, 0, 0, 0, 241, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
-                    // [7, 6, 11, 254, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 122, 232, 0, 8, 1, 81, 0, 0, 88, 11, 0, 32, 186, 22, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
-                    // [5, 0, 254, 32, 96, 23, 0, 32, 24, 11, 0, 32, 221, 33, 0, 8, 2, 0, 0, 0, 241, 161, 0, 0, 0, 0, 0, 0, 241, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
-                    // [6, 5, 9, 254, 0, 0, 0, 0, 5, 9, 1, 0, 5, 9, 0, 0, 122, 232, 0, 8, 56, 113, 0, 0, 186, 22, 0, 32, 186, 22, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
-                    // [7, 5, 9, 254, 97, 71, 0, 8, 0, 0, 0, 0, 186, 22, 0, 32, 122, 232, 0, 8, 56, 113, 0, 0, 242, 22, 0, 32, 186, 22, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
-                    // [6, 0, 5, 254, 0, 0, 0, 0, 0, 5, 1, 0, 0, 5, 0, 0, 122, 232, 0, 8, 44, 0, 0, 0, 88, 11, 0, 32, 186, 22, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
-                    // [7, 0, 5, 254, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 122, 232, 0, 8, 44, 0, 0, 0, 88, 11, 0, 32, 186, 22, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+pub const MOONLANDER_VENDOR_ID: u16 = 0x3297;
+pub const MOONLANDER_PRODUCT_ID: u16 = 0x1969;
 
-                    let mut buf = [0u8; 64];
-                    while let Ok(_) = dev.read(&mut buf) {
-                        println!("{:?}", buf);
-                    }
-                }
-                Err(e) => {
-                    println!("Error: {}", e);
-                }
-            }
-        },
-        Err(e) => {
-            eprintln!("Error: {}", e);
+/// A connected HID device, as `hid list` shows it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Device {
+    pub vendor_id: u16,
+    pub product_id: u16,
+    pub manufacturer: Option<String>,
+    pub product: Option<String>,
+    pub interface_number: i32,
+    pub usage_page: u16,
+    pub usage: u16,
+}
+
+impl fmt::Display for Device {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04x}:{:04x} {} {} (interface {}, usage page {:#06x}, usage {:#04x})",
+            self.vendor_id,
+            self.product_id,
+            self.manufacturer.as_deref().unwrap_or("?"),
+            self.product.as_deref().unwrap_or("?"),
+            self.interface_number,
+            self.usage_page,
+            self.usage
+        )
+    }
+}
+
+/// Lists every HID device, one entry per interface.
+pub fn list_devices() -> HidResult<Vec<Device>> {
+    let api = HidApi::new()?;
+    Ok(api
+        .device_list()
+        .map(|device| Device {
+            vendor_id: device.vendor_id(),
+            product_id: device.product_id(),
+            manufacturer: device.manufacturer_string().map(String::from),
+            product: device.product_string().map(String::from),
+            interface_number: device.interface_number(),
+            usage_page: device.usage_page(),
+            usage: device.usage(),
+        })
+        .collect())
+}
+
+/// Opens a device and calls `on_report` with every raw report it sends, until `on_report`
+/// returns false or reading fails.
+pub fn read_reports(
+    vendor_id: u16,
+    product_id: u16,
+    mut on_report: impl FnMut(&[u8]) -> bool,
+) -> HidResult<()> {
+    let device = HidApi::new()?.open(vendor_id, product_id)?;
+    // once we open the device we should do the handshake to start receiving raw events
+    // for testing, I did this with the oryx tool in the browser and then started receiving raw HID reports:
+    // [6, 5, 9, 254, 0, 0, 0, 0, 5, 9, 1, 0, 5, 9, 0, 0, 122, 232, 0, 8, 56, 113, 0, 0, 186, 22, 0, 32, 186, 22, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    // [7, 5, 9, 254, 97, 71, 0, 8, 0, 0, 0, 0, 186, 22, 0, 32, 122, 232, 0, 8, 56, 113, 0, 0, 234, 22, 0, 32, 186, 22, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    // [6, 6, 11, 254, 0, 0, 0, 0, 6, 11, 1, 0, 6, 11, 0, 0, 122, 232, 0, 8, 1, 81, 0, 0, 88, 11, 0, 32, 186, 22, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    // [5, 1, 254, 32, 96, 23, 0, 32, 24, 11, 0, 32, 221, 33, 0, 8, 0, 0, 0, 0, 241, 161, 0, 0, 1, 0, 0, 0, 241, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    // [7, 6, 11, 254, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 122, 232, 0, 8, 1, 81, 0, 0, 88, 11, 0, 32, 186, 22, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    // [5, 0, 254, 32, 96, 23, 0, 32, 24, 11, 0, 32, 221, 33, 0, 8, 2, 0, 0, 0, 241, 161, 0, 0, 0, 0, 0, 0, 241, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    // [6, 6, 11, 254, 0, 0, 0, 0, 6, 11, 1, 0, 6, 11, 0, 0, 122, 232, 0, 8, 1, 81, 0, 0, 88, 11, 0, 32, 186, 22, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    // [5, 1, 254, 32, 96, 23, 0, 32, 24, 11, 0, 32, 221, 33, 0, 8, 0, 0, 0, 0, 241, 161, 0, 0, 1, 0, 0, 0, 241, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    // [7, 6, 11, 254, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 122, 232, 0, 8, 1, 81, 0, 0, 88, 11, 0, 32, 186, 22, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    // [5, 0, 254, 32, 96, 23, 0, 32, 24, 11, 0, 32, 221, 33, 0, 8, 2, 0, 0, 0, 241, 161, 0, 0, 0, 0, 0, 0, 241, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    // [6, 5, 9, 254, 0, 0, 0, 0, 5, 9, 1, 0, 5, 9, 0, 0, 122, 232, 0, 8, 56, 113, 0, 0, 186, 22, 0, 32, 186, 22, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    // [7, 5, 9, 254, 97, 71, 0, 8, 0, 0, 0, 0, 186, 22, 0, 32, 122, 232, 0, 8, 56, 113, 0, 0, 242, 22, 0, 32, 186, 22, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    // [6, 0, 5, 254, 0, 0, 0, 0, 0, 5, 1, 0, 0, 5, 0, 0, 122, 232, 0, 8, 44, 0, 0, 0, 88, 11, 0, 32, 186, 22, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    // [7, 0, 5, 254, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 122, 232, 0, 8, 44, 0, 0, 0, 88, 11, 0, 32, 186, 22, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+
+    let mut buf = [0u8; 64];
+    loop {
+        let size = device.read(&mut buf)?;
+        if !on_report(&buf[..size]) {
+            return Ok(());
         }
     }
 }
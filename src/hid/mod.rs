@@ -1 +1,2 @@
-pub(crate) mod hid;
+#[allow(clippy::module_inception)]
+pub mod hid;
@@ -0,0 +1,16 @@
+//! Drill generation and typing sessions for practicing on a Moonlander keyboard.
+//!
+//! Generators in [`generators`] are combined into a [`GeneratorGraph`], whose root produces the
+//! patterns a [`TypingSession`] asks the user to type. The session has no UI of its own: it is
+//! fed keys and reports whether they were correct, so it can be embedded in other tools.
+
+pub mod export;
+pub mod generators;
+pub mod hid;
+pub mod session;
+
+pub use export::{DrillSet, ExportFormat};
+pub use generators::error::GeneratorError;
+pub use generators::graph::GeneratorGraph;
+pub use generators::{TypingPattern, TypingPatternGenerator};
+pub use session::{KeyResult, SessionConfig, TypingSession};
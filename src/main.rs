@@ -1,70 +1,402 @@
-mod generators;
-mod hid;
+mod cli;
 
-extern crate hidapi;
+use clap::{Args, Parser, Subcommand};
+use cli::practice::Reload;
+use moonlander_trainer::generators::coding::create_tree_graph;
+use moonlander_trainer::generators::graph::{GeneratorGraph, Severity};
+use moonlander_trainer::generators::preview::Preview;
+use moonlander_trainer::generators::random;
+use moonlander_trainer::generators::script::ScriptGenerator;
+use moonlander_trainer::generators::template::TemplateGenerator;
+use moonlander_trainer::hid::hid::{list_devices, MOONLANDER_PRODUCT_ID, MOONLANDER_VENDOR_ID};
+use moonlander_trainer::session::log::SessionLog;
+use moonlander_trainer::session::{Indentation, SessionConfig, TypingSession};
+use moonlander_trainer::{DrillSet, ExportFormat, TypingPatternGenerator};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fs;
+use std::io::{self, Write};
+use std::process::ExitCode;
+use std::sync::Arc;
 
-use crate::generators::coding::{create_coding_generators, create_method_call_generator};
-use crate::generators::sequences::RandomRepeatGenerator;
-use generators::sequences::RepeatPatternGenerator;
-use generators::simple::ListOfPatternsGenerator;
-use generators::TypingPatternGenerator;
+/// Generators with up to this many ways of generating are enumerated by `count`, to count the
+/// distinct patterns.
+const DISTINCT_COUNT_LIMIT: u128 = 100_000;
 
-use crate::generators::randomized::WeightedPatternGenerator;
-use std::cell::RefCell;
-use std::collections::HashMap;
-use std::rc::Rc;
+/// Generates typing drills for code and practices them.
+#[derive(Debug, Parser)]
+#[command(
+    version,
+    after_help = "Exit codes: 0 on success, 1 on failure or when check finds errors, 2 on usage errors."
+)]
+struct Cli {
+    /// Load the generator graph from a snapshot instead of using the default drill tree.
+    #[arg(long, global = true, value_name = "PATH")]
+    graph: Option<String>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Print patterns of a generator.
+    Generate {
+        #[command(flatten)]
+        generator: GeneratorArgs,
+        /// How many patterns to print.
+        #[arg(short = 'n', long, default_value_t = 10)]
+        count: usize,
+        /// `text`, `json` with provenance, `monkeytype` custom text (with the pipe delimiter), or a `markdown` or `html`
+        /// worksheet.
+        #[arg(long, default_value = "text")]
+        format: ExportFormat,
+        /// Write the patterns to this file instead of stdout.
+        #[arg(short, long, value_name = "PATH")]
+        output: Option<String>,
+    },
+    /// Type drills in the terminal.
+    Practice {
+        #[command(flatten)]
+        generator: GeneratorArgs,
+        /// Stop after this many drills.
+        #[arg(short = 'n', long, default_value_t = 10)]
+        count: u32,
+        /// `tabs`, or the number of spaces per indentation level.
+        #[arg(long, default_value = "4")]
+        indentation: Indentation,
+        /// Leading whitespace is shown but not typed, like in an editor that indents new lines.
+        #[arg(long)]
+        auto_indent: bool,
+        /// Closing brackets and quotes are inserted with their opener and typed over or skipped
+        /// with Tab.
+        #[arg(long)]
+        auto_pair: bool,
+        /// Append the keystrokes to this session log.
+        #[arg(long, value_name = "PATH")]
+        log: Option<String>,
+        /// Rebuild the generator whenever the `--graph` snapshot or the script changes.
+        #[arg(long)]
+        watch: bool,
+    },
+    /// Summarize session logs: speed, accuracy and the weakest n-grams.
+    Stats {
+        #[arg(required = true)]
+        logs: Vec<String>,
+        /// N-gram sizes to analyze.
+        #[arg(long, value_delimiter = ',', default_value = "2,3")]
+        sizes: Vec<usize>,
+        /// How many of the weakest n-grams to list.
+        #[arg(long, default_value_t = 10)]
+        weakest: usize,
+        /// Leave out n-grams typed fewer times than this.
+        #[arg(long, default_value_t = 3)]
+        min_samples: u32,
+    },
+    /// Work with the keyboard's raw HID interface.
+    Hid {
+        #[command(subcommand)]
+        command: HidCommand,
+    },
+    /// Check the generator graph for invalid settings, cycles and unreachable generators.
+    Check,
+    /// Print the generator graph in Graphviz's DOT language.
+    Dot,
+    /// Print the generator graph as an indented outline.
+    Outline,
+    /// Print how many patterns every generator can produce.
+    Count,
+    /// List every pattern of a generator with a limited number of them.
+    Enumerate {
+        name: String,
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// Print statistics over many patterns of a generator.
+    Preview {
+        #[command(flatten)]
+        generator: GeneratorArgs,
+        #[arg(short = 'n', long, default_value_t = 1000)]
+        samples: usize,
+    },
+    /// Print the generator graph as a JSON snapshot, or save it.
+    Snapshot { path: Option<String> },
+}
+
+#[derive(Debug, Args)]
+struct GeneratorArgs {
+    /// The named generator to use instead of the root of the graph.
+    #[arg(short, long, value_name = "NAME", conflicts_with_all = ["script", "template"])]
+    generator: Option<String>,
+    /// Generate with a Rhai script, which can call every generator of the graph by name.
+    #[arg(long, value_name = "PATH", conflicts_with = "template")]
+    script: Option<String>,
+    /// Fill a template like `{symbols}[{number}]` with the generators of the graph.
+    #[arg(long)]
+    template: Option<String>,
+    /// Seed the random number generator to reproduce the same patterns.
+    #[arg(long)]
+    seed: Option<u64>,
+}
 
-fn main() {
-    let coding_generator = create_coding_generators();
-    let number_arguments = Rc::new(RepeatPatternGenerator::new(
-        "arguments",
-        coding_generator.number_list.clone(),
-        HashMap::from([("count", "3".to_string()), ("delimiter", ", ".to_string())]),
-    ));
-    let method_call_generator = create_method_call_generator(
-        "method_call",
-        coding_generator.symbols.clone(),
-        coding_generator.open_paren.clone(),
-        coding_generator.close_paren.clone(),
-        number_arguments.clone(),
-        ", ".to_string(),
-        0,
-        2,
-    );
+#[derive(Debug, Subcommand)]
+enum HidCommand {
+    /// List all HID devices.
+    List,
+    /// Record the raw reports of a device.
+    Capture {
+        #[command(flatten)]
+        device: DeviceArgs,
+        /// Write the reports to this file instead of stdout.
+        #[arg(short, long, value_name = "PATH")]
+        output: Option<String>,
+        /// Stop after this many reports.
+        #[arg(short = 'n', long)]
+        count: Option<u64>,
+    },
+    /// Print the raw reports of a device as they arrive.
+    Monitor {
+        #[command(flatten)]
+        device: DeviceArgs,
+    },
+}
 
-    let mut tree_content = Rc::new(RefCell::new(WeightedPatternGenerator::new(
-        "tree_content",
-        vec![
-            (1f32, coding_generator.number.clone()),
-            (1f32, coding_generator.array_deref.clone()),
-            (1f32, method_call_generator.clone()),
-        ],
-    )));
+#[derive(Debug, Args)]
+struct DeviceArgs {
+    /// Vendor ID in hex.
+    #[arg(long, value_parser = parse_hex, default_value = "3297")]
+    vendor_id: u16,
+    /// Product ID in hex.
+    #[arg(long, value_parser = parse_hex, default_value = "1969")]
+    product_id: u16,
+}
 
-    let repeated_subtrees = Rc::new(RandomRepeatGenerator::new(
-        "repeated_subtrees",
-        tree_content.clone(),
-        HashMap::from([
-            ("delimiter", ", ".to_string()),
-            ("min_count", "1".to_string()),
-            ("max_count", "3".to_string()),
-        ]),
-    ));
+fn parse_hex(s: &str) -> Result<u16, String> {
+    u16::from_str_radix(s.trim_start_matches("0x"), 16).map_err(|e| e.to_string())
+}
 
-    let value = Box::new(ListOfPatternsGenerator::new(
-        "tree",
-        vec![
-            coding_generator.open_bracket.clone(),
-            repeated_subtrees.clone(),
-            coding_generator.close_bracket.clone(),
-        ],
-        HashMap::from([("delimiter", "".to_string())]),
-    ));
-    let mut tree_generator = Rc::new(RefCell::new(value));
-    // tree_content.borrow_mut().patterns[1] = (5f32, repeated_subtrees.clone());
+impl GeneratorArgs {
+    /// Seeds the random number generator if asked to and returns the chosen generator.
+    fn select(
+        &self,
+        graph: &GeneratorGraph,
+    ) -> Result<Arc<dyn TypingPatternGenerator>, Box<dyn Error>> {
+        let generator = self.build(graph)?;
+        // Checking a script runs it once, so seed afterwards to start from the same state.
+        if let Some(seed) = self.seed {
+            random::seed(seed);
+        }
+        Ok(generator)
+    }
+
+    fn build(
+        &self,
+        graph: &GeneratorGraph,
+    ) -> Result<Arc<dyn TypingPatternGenerator>, Box<dyn Error>> {
+        let mut generators = graph.nodes();
+        generators.extend(graph.named.iter().cloned());
+        if let Some(path) = &self.script {
+            let generator = ScriptGenerator::new(
+                "script",
+                generators,
+                HashMap::from([("path", path.clone())]),
+            )?;
+            return Ok(Arc::new(generator));
+        }
+        if let Some(template) = &self.template {
+            let generator = TemplateGenerator::new(
+                "template",
+                generators,
+                HashMap::from([("template", template.clone())]),
+            )?;
+            return Ok(Arc::new(generator));
+        }
+        match &self.generator {
+            Some(name) => Ok(graph
+                .find(name)
+                .ok_or_else(|| format!("There is no generator named {:?}", name))?),
+            None => Ok(graph.root.clone()),
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(code) => code,
+        // The reader went away, e.g. `count | head` has all it wants.
+        Err(e)
+            if e.downcast_ref::<io::Error>()
+                .is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe) =>
+        {
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
 
-    // hid::hid::test_hidapi();
-    for _ in 0..100 {
-        println!("{}", tree_generator.borrow().generate().pattern);
+fn run(cli: Cli) -> Result<ExitCode, Box<dyn Error>> {
+    let graph = || -> Result<GeneratorGraph, Box<dyn Error>> {
+        Ok(match &cli.graph {
+            Some(path) => GeneratorGraph::load(path)?,
+            None => create_tree_graph()?,
+        })
+    };
+    let mut out = io::stdout();
+    match cli.command {
+        Command::Generate {
+            generator: args,
+            count,
+            format,
+            output,
+        } => {
+            let graph = graph()?;
+            let generator = args.select(&graph)?;
+            let drills =
+                DrillSet::generate(generator.as_ref(), count, args.seed).with_graph(&generator);
+            let exported = drills.export(format);
+            match output {
+                Some(path) => fs::write(path, exported)?,
+                None => write!(out, "{}", exported)?,
+            }
+        }
+        Command::Practice {
+            generator,
+            count,
+            indentation,
+            auto_indent,
+            auto_pair,
+            log,
+            watch,
+        } => {
+            let config = SessionConfig {
+                indentation,
+                auto_indent,
+                auto_pair,
+            };
+            let mut session = TypingSession::new(generator.select(&graph()?)?, config);
+            let paths: Vec<String> = cli.graph.iter().chain(&generator.script).cloned().collect();
+            if watch && paths.is_empty() {
+                return Err("--watch needs a --graph snapshot or a --script to watch".into());
+            }
+            let reload = watch.then(|| {
+                Reload::new(paths, || {
+                    let graph = graph()?;
+                    if let Some(lint) = graph
+                        .check()
+                        .into_iter()
+                        .find(|lint| lint.severity == Severity::Error)
+                    {
+                        return Err(lint.to_string().into());
+                    }
+                    generator.build(&graph)
+                })
+            });
+            cli::practice::practice(&mut session, count, reload)?;
+            // Save the log first, a closed stdout mustn't lose it.
+            if let Some(path) = log {
+                session.log.append_to(path)?;
+            }
+            let stats = &session.stats;
+            writeln!(
+                out,
+                "{} drills, {} keystrokes, {} errors",
+                stats.completed, stats.keystrokes, stats.errors
+            )?;
+        }
+        Command::Stats {
+            logs,
+            sizes,
+            weakest,
+            min_samples,
+        } => {
+            let log = SessionLog::load_all(&logs)?;
+            cli::stats::print_stats(&mut out, &log, &sizes, weakest, min_samples)?;
+        }
+        Command::Hid { command } => match command {
+            HidCommand::List => {
+                for device in list_devices()? {
+                    let marker = if (device.vendor_id, device.product_id)
+                        == (MOONLANDER_VENDOR_ID, MOONLANDER_PRODUCT_ID)
+                    {
+                        "*"
+                    } else {
+                        " "
+                    };
+                    writeln!(out, "{} {}", marker, device)?;
+                }
+            }
+            HidCommand::Capture {
+                device,
+                output,
+                count,
+            } => cli::hid::capture(
+                device.vendor_id,
+                device.product_id,
+                output.as_deref(),
+                count,
+            )?,
+            HidCommand::Monitor { device } => {
+                cli::hid::monitor(device.vendor_id, device.product_id)?
+            }
+        },
+        Command::Check => {
+            let lints = graph()?.check();
+            for lint in &lints {
+                writeln!(out, "{}", lint)?;
+            }
+            if lints.is_empty() {
+                writeln!(out, "No problems found.")?;
+            }
+            if lints.iter().any(|lint| lint.severity == Severity::Error) {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Command::Dot => write!(out, "{}", graph()?.to_dot())?,
+        Command::Outline => write!(out, "{}", graph()?.outline())?,
+        Command::Count => {
+            let graph = graph()?;
+            for (node, cardinality) in graph.nodes().iter().zip(graph.cardinalities()) {
+                let count = match cardinality {
+                    // Different ways of generating can give the same text, so small sets are
+                    // listed to count what is distinct.
+                    Some(cardinality) if cardinality <= DISTINCT_COUNT_LIMIT => {
+                        match GeneratorGraph::new(node.clone(), Vec::new()).enumerate() {
+                            Some(patterns) => {
+                                patterns.collect::<HashSet<String>>().len().to_string()
+                            }
+                            None => format!("at most {}", cardinality),
+                        }
+                    }
+                    Some(cardinality) => format!("at most {}", cardinality),
+                    None => String::from("unlimited"),
+                };
+                writeln!(out, "{:<30} {}", node.name(), count)?;
+            }
+        }
+        Command::Enumerate { name, limit } => {
+            let generator = graph()?
+                .find(&name)
+                .ok_or_else(|| format!("There is no generator named {:?}", name))?;
+            let patterns = GeneratorGraph::new(generator, Vec::new())
+                .enumerate()
+                .ok_or_else(|| format!("{} has no limited number of patterns", name))?;
+            for pattern in patterns.take(limit.unwrap_or(usize::MAX)) {
+                writeln!(out, "{}", pattern)?;
+            }
+        }
+        Command::Preview { generator, samples } => {
+            let generator = generator.select(&graph()?)?;
+            write!(out, "{}", Preview::sample(generator.as_ref(), samples))?;
+        }
+        Command::Snapshot { path } => {
+            let graph = graph()?;
+            match path {
+                Some(path) => graph.save(path)?,
+                None => writeln!(out, "{}", serde_json::to_string_pretty(&graph.snapshot()?)?)?,
+            }
+        }
     }
+    Ok(ExitCode::SUCCESS)
 }
@@ -0,0 +1,59 @@
+/// Pairs an editor closes automatically when the opening character is typed.
+const PAIRS: [(char, char); 6] = [
+    ('(', ')'),
+    ('[', ']'),
+    ('{', '}'),
+    ('"', '"'),
+    ('\'', '\''),
+    ('`', '`'),
+];
+
+fn is_quote(c: char) -> bool {
+    matches!(c, '"' | '\'' | '`')
+}
+
+/// Finds the characters an auto-pairing editor would have inserted when their opener was typed.
+///
+/// Returns the index of the opener for every closer of a pair, and `None` for all other characters.
+/// Like most editors, brackets inside quotes are not paired and quotes escaped with `\` are plain
+/// characters. Openers without a closer in `text` are treated as plain characters, as if the
+/// editor had not closed them. A `'` after a letter or digit is an apostrophe, as in `don't`.
+pub fn find_auto_closed(text: &[char]) -> Vec<Option<usize>> {
+    let mut closed = vec![None; text.len()];
+    let mut open: Vec<(usize, char)> = Vec::new();
+    let mut escaped = false;
+    for (i, &c) in text.iter().enumerate() {
+        let in_quote = matches!(open.last(), Some((_, quote)) if is_quote(*quote));
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        if in_quote && c == '\\' {
+            escaped = true;
+            continue;
+        }
+
+        if let Some(&(opener, expected)) = open.last() {
+            if c == expected {
+                closed[i] = Some(opener);
+                open.pop();
+                continue;
+            }
+        }
+        if in_quote {
+            continue;
+        }
+        let apostrophe = c == '\'' && i > 0 && text[i - 1].is_alphanumeric();
+        if apostrophe {
+            continue;
+        }
+        if let Some((_, closer)) = PAIRS.iter().find(|(o, _)| *o == c) {
+            open.push((i, *closer));
+        } else if PAIRS.iter().any(|(_, closer)| *closer == c) {
+            // A closer that does not match the innermost opener, e.g. in a closing run like `)]`.
+            // Everything still open stays unpaired.
+            open.clear();
+        }
+    }
+    closed
+}
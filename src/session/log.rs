@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+
+/// A single keystroke typed during a session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeystrokeEvent {
+    /// Name of the generator that produced the pattern being typed.
+    pub pattern: String,
+    /// Index of the expected character within the pattern.
+    pub position: usize,
+    pub expected: char,
+    pub typed: char,
+    /// Time since the previous keystroke.
+    pub elapsed_ms: u64,
+}
+
+impl KeystrokeEvent {
+    pub fn is_error(&self) -> bool {
+        self.expected != self.typed
+    }
+}
+
+/// Keystroke events of one or more sessions, stored as one JSON object per line.
+#[derive(Debug, Default)]
+pub struct SessionLog {
+    pub events: Vec<KeystrokeEvent>,
+}
+
+impl SessionLog {
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut events = Vec::new();
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                events.push(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(SessionLog { events })
+    }
+
+    /// Loads and concatenates several logs, e.g. all sessions in a directory.
+    pub fn load_all<P: AsRef<Path>>(paths: &[P]) -> io::Result<Self> {
+        let mut log = SessionLog::default();
+        for path in paths {
+            log.events.append(&mut SessionLog::load(path)?.events);
+        }
+        Ok(log)
+    }
+
+    pub fn append_to<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.write(OpenOptions::new().create(true).append(true).open(path)?)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.write(File::create(path)?)
+    }
+
+    fn write(&self, mut writer: impl Write) -> io::Result<()> {
+        for event in &self.events {
+            writeln!(writer, "{}", serde_json::to_string(event)?)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,380 @@
+pub mod autopair;
+pub mod log;
+pub mod ngrams;
+
+use crate::generators::{TypingPattern, TypingPatternGenerator};
+use crate::session::log::{KeystrokeEvent, SessionLog};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// How the session renders the indentation levels of a pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Indentation {
+    Tabs,
+    Spaces(usize),
+}
+
+impl Indentation {
+    fn unit(&self) -> String {
+        match self {
+            Indentation::Tabs => String::from("\t"),
+            Indentation::Spaces(width) => " ".repeat(*width),
+        }
+    }
+}
+
+impl FromStr for Indentation {
+    type Err = String;
+
+    /// Parses `tabs`, or the number of spaces per level.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tabs" => Ok(Indentation::Tabs),
+            _ => s
+                .parse()
+                .map(Indentation::Spaces)
+                .map_err(|_| format!("expected tabs or a number of spaces, got {:?}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SessionConfig {
+    pub indentation: Indentation,
+    /// The editor indents new lines by itself, so leading whitespace is shown but not typed.
+    pub auto_indent: bool,
+    /// The editor inserts the closer when an opening bracket or quote is typed.
+    pub auto_pair: bool,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        SessionConfig {
+            indentation: Indentation::Spaces(4),
+            auto_indent: false,
+            auto_pair: false,
+        }
+    }
+}
+
+/// A key pressed during a session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Backspace,
+    /// Moves the cursor over a closer the editor inserted, like Tab or the arrow keys do.
+    Skip,
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Key::Char(c) => write!(f, "{}", c),
+            Key::Backspace => write!(f, "⌫"),
+            Key::Skip => write!(f, "⇥"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Input {
+    /// The character has to be typed.
+    Typed,
+    /// The editor produces the character, e.g. auto-indentation.
+    Editor,
+    /// The editor produced the character in advance and the cursor has to skip over it,
+    /// e.g. the closing brace of a multi-line block.
+    Skip,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DrillChar {
+    pub c: char,
+    pub input: Input,
+    /// For closers the editor inserted automatically, the index of their opener.
+    pub opener: Option<usize>,
+}
+
+/// A pattern as the session shows it, with the keystrokes it expects.
+#[derive(Debug, Clone)]
+pub struct Drill {
+    pub name: String,
+    pub chars: Vec<DrillChar>,
+}
+
+impl Drill {
+    pub fn new(pattern: &TypingPattern, config: &SessionConfig) -> Self {
+        let mut chars = Vec::new();
+        let leading_input = if config.auto_indent {
+            Input::Editor
+        } else {
+            Input::Typed
+        };
+        for (i, line) in pattern.pattern.split('\n').enumerate() {
+            if i > 0 {
+                chars.push(('\n', Input::Typed));
+            }
+            let content = line.trim_start_matches('\t');
+            let levels = line.len() - content.len();
+            let indentation = config.indentation.unit().repeat(levels);
+            let leading_whitespace = indentation
+                .chars()
+                .chain(content.chars().take_while(|c| c.is_whitespace()));
+            for c in leading_whitespace {
+                chars.push((c, leading_input));
+            }
+            for c in content.trim_start().chars() {
+                chars.push((c, Input::Typed));
+            }
+        }
+
+        let mut chars: Vec<DrillChar> = chars
+            .into_iter()
+            .map(|(c, input)| DrillChar {
+                c,
+                input,
+                opener: None,
+            })
+            .collect();
+        if config.auto_pair {
+            let text: Vec<char> = chars.iter().map(|c| c.c).collect();
+            for (i, opener) in autopair::find_auto_closed(&text).into_iter().enumerate() {
+                let Some(opener) = opener else { continue };
+                chars[i].opener = Some(opener);
+                // Pressing enter between a pair moves the closer to its own line, so the newline
+                // and indentation in front of it are already there when the body is done.
+                if text[opener..i].contains(&'\n') {
+                    chars[i].input = Input::Skip;
+                    let mut j = i;
+                    while j > opener + 1 && text[j - 1].is_whitespace() {
+                        j -= 1;
+                        chars[j].input = Input::Editor;
+                        if text[j] == '\n' {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        Drill {
+            name: pattern.name.clone(),
+            chars,
+        }
+    }
+
+    /// The text as it appears on screen.
+    pub fn text(&self) -> String {
+        self.chars.iter().map(|c| c.c).collect()
+    }
+
+    /// The keys the user has to press, in order.
+    pub fn expected_keys(&self) -> Vec<Key> {
+        self.chars
+            .iter()
+            .filter_map(|c| match c.input {
+                Input::Typed => Some(Key::Char(c.c)),
+                Input::Editor => None,
+                Input::Skip => Some(Key::Skip),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PatternStats {
+    pub completed: u32,
+    pub keystrokes: u32,
+    pub errors: u32,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct SessionStats {
+    pub keystrokes: u32,
+    pub errors: u32,
+    pub completed: u32,
+    /// Statistics per name of the generator that created the pattern.
+    pub patterns: HashMap<String, PatternStats>,
+}
+
+impl SessionStats {
+    fn record(&mut self, pattern: &str, correct: bool, completed: bool) {
+        let pattern_stats = self.patterns.entry(pattern.to_string()).or_default();
+        pattern_stats.keystrokes += 1;
+        self.keystrokes += 1;
+        if !correct {
+            pattern_stats.errors += 1;
+            self.errors += 1;
+        }
+        if completed {
+            pattern_stats.completed += 1;
+            self.completed += 1;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyResult {
+    Correct,
+    Mistyped,
+    /// The key was correct and finished the drill. The session moved on to a new one.
+    Completed,
+}
+
+/// A typing session without any UI: it generates drills and checks the keys fed to it.
+#[derive(Debug)]
+pub struct TypingSession {
+    generator: Arc<dyn TypingPatternGenerator>,
+    config: SessionConfig,
+    drill: Drill,
+    /// Index into the chars of the drill of the next character to type.
+    position: usize,
+    /// The last key was wrong and the cursor is waiting for it to be corrected.
+    mistyped: bool,
+    pub stats: SessionStats,
+    pub log: SessionLog,
+}
+
+impl TypingSession {
+    pub fn new(generator: Arc<dyn TypingPatternGenerator>, config: SessionConfig) -> Self {
+        let mut session = TypingSession {
+            generator,
+            config,
+            drill: Drill {
+                name: String::new(),
+                chars: Vec::new(),
+            },
+            position: 0,
+            mistyped: false,
+            stats: SessionStats::default(),
+            log: SessionLog::default(),
+        };
+        session.next_drill();
+        session
+    }
+
+    pub fn drill(&self) -> &Drill {
+        &self.drill
+    }
+
+    /// Index into the chars of the drill of the next character to type.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Whether the last key was wrong. Backspace clears the error without moving the cursor.
+    pub fn mistyped(&self) -> bool {
+        self.mistyped
+    }
+
+    pub fn config(&self) -> &SessionConfig {
+        &self.config
+    }
+
+    /// Replaces the generator of the session. The drill in progress and the statistics are kept,
+    /// only the following drills come from the new generator.
+    pub fn set_generator(&mut self, generator: Arc<dyn TypingPatternGenerator>) {
+        self.generator = generator;
+    }
+
+    /// Feeds a typed character to the session, `elapsed_ms` after the previous one.
+    pub fn type_char(&mut self, typed: char, elapsed_ms: u64) -> KeyResult {
+        self.press(Key::Char(typed), elapsed_ms)
+    }
+
+    /// Feeds a key to the session, `elapsed_ms` after the previous one.
+    ///
+    /// Characters overtype closers the editor inserted, and `Key::Skip` moves over them. Backspace
+    /// removes the last typed character, together with its closer if it opened an auto-closed pair.
+    pub fn press(&mut self, key: Key, elapsed_ms: u64) -> KeyResult {
+        let Some(expected) = self.drill.chars.get(self.position).copied() else {
+            // The generator only produced drills with nothing to type.
+            self.next_drill();
+            return KeyResult::Completed;
+        };
+        if key == Key::Backspace {
+            if self.mistyped {
+                self.mistyped = false;
+            } else {
+                self.backspace();
+            }
+            return KeyResult::Correct;
+        }
+        if let Key::Char(typed) = key {
+            self.log.events.push(KeystrokeEvent {
+                pattern: self.drill.name.clone(),
+                position: self.position,
+                expected: expected.c,
+                typed,
+                elapsed_ms,
+            });
+        }
+
+        let correct = match (key, expected.input) {
+            (Key::Char(typed), Input::Typed) => typed == expected.c,
+            (Key::Skip, Input::Skip) => true,
+            (Key::Skip, Input::Typed) => expected.opener.is_some(),
+            _ => false,
+        };
+        if correct {
+            self.position += 1;
+            self.skip_untyped();
+        }
+        self.mistyped = !correct;
+        let completed = self.position == self.drill.chars.len();
+        self.stats.record(&self.drill.name, correct, completed);
+        if !correct {
+            return KeyResult::Mistyped;
+        }
+        if !completed {
+            return KeyResult::Correct;
+        }
+        self.next_drill();
+        KeyResult::Completed
+    }
+
+    /// Indices of the closers the editor has inserted ahead of the cursor, which are shown
+    /// although they have not been reached yet.
+    pub fn pending_closers(&self) -> Vec<usize> {
+        (self.position..self.drill.chars.len())
+            .filter(
+                |&i| matches!(self.drill.chars[i].opener, Some(opener) if opener < self.position),
+            )
+            .collect()
+    }
+
+    fn backspace(&mut self) {
+        // Characters produced by the editor go away together with what produced them.
+        while self.position > 0 {
+            self.position -= 1;
+            if self.drill.chars[self.position].input != Input::Editor {
+                break;
+            }
+        }
+        if self.position == 0 && self.drill.chars[0].input == Input::Editor {
+            self.skip_untyped();
+        }
+    }
+
+    /// Abandons the current drill and generates a new one, skipping drills with nothing to type.
+    pub fn next_drill(&mut self) {
+        for _ in 0..100 {
+            self.drill = Drill::new(&self.generator.generate(), &self.config);
+            self.position = 0;
+            self.mistyped = false;
+            self.skip_untyped();
+            if self.position < self.drill.chars.len() {
+                break;
+            }
+        }
+    }
+
+    fn skip_untyped(&mut self) {
+        while self.position < self.drill.chars.len()
+            && self.drill.chars[self.position].input == Input::Editor
+        {
+            self.position += 1;
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use crate::session::log::SessionLog;
+use std::collections::HashMap;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct NgramStat {
+    /// How often the n-gram was typed.
+    pub count: u32,
+    /// Time spent typing everything after the first character, summed over all occurrences.
+    pub total_ms: u64,
+    /// Occurrences in which at least one of its characters was mistyped.
+    pub errors: u32,
+}
+
+impl NgramStat {
+    pub fn mean_ms(&self) -> f64 {
+        self.total_ms as f64 / self.count as f64
+    }
+
+    pub fn error_rate(&self) -> f64 {
+        self.errors as f64 / self.count as f64
+    }
+
+    /// Higher is weaker. Every error in an occurrence counts as much as doubling its time.
+    pub fn weakness(&self) -> f64 {
+        self.mean_ms() * (1.0 + self.error_rate())
+    }
+}
+
+/// Timing and error statistics for the n-grams typed in a session log.
+#[derive(Debug, Default)]
+pub struct NgramStats {
+    pub ngrams: HashMap<String, NgramStat>,
+}
+
+impl NgramStats {
+    /// Collects statistics for n-grams of every size in `sizes`, e.g. `&[2, 3]` for bigrams and trigrams.
+    ///
+    /// N-grams never span two patterns. Mistyped keystrokes add their time and an error
+    /// to the character they were meant to type, which is counted once it is typed correctly.
+    /// Backspaces aren't logged, but typing at an earlier position shows them: the characters
+    /// from there on were deleted and don't form n-grams with the ones typed again.
+    pub fn from_log(log: &SessionLog, sizes: &[usize]) -> Self {
+        let mut stats = NgramStats::default();
+        // Typed characters of the current pattern, with their position, their time and whether
+        // they were mistyped.
+        let mut typed: Vec<(usize, char, u64, bool)> = Vec::new();
+        let mut pending_ms = 0;
+        let mut pending_error = false;
+        for event in &log.events {
+            // A new pattern starts at an earlier position too.
+            while typed
+                .last()
+                .is_some_and(|&(position, ..)| position >= event.position)
+            {
+                typed.pop();
+            }
+            pending_ms += event.elapsed_ms;
+            if event.is_error() {
+                pending_error = true;
+                continue;
+            }
+            typed.push((event.position, event.expected, pending_ms, pending_error));
+            pending_ms = 0;
+            pending_error = false;
+
+            for &size in sizes {
+                if size < 2 || typed.len() < size {
+                    continue;
+                }
+                let window = &typed[typed.len() - size..];
+                let stat = stats
+                    .ngrams
+                    .entry(window.iter().map(|(_, c, _, _)| c).collect())
+                    .or_default();
+                stat.count += 1;
+                stat.total_ms += window[1..].iter().map(|(_, _, ms, _)| ms).sum::<u64>();
+                if window.iter().any(|(_, _, _, error)| *error) {
+                    stat.errors += 1;
+                }
+            }
+        }
+        stats
+    }
+
+    /// The `count` weakest n-grams that were typed at least `min_samples` times, weakest first.
+    pub fn weakest(&self, count: usize, min_samples: u32) -> Vec<(&str, &NgramStat)> {
+        let mut ngrams: Vec<(&str, &NgramStat)> = self
+            .ngrams
+            .iter()
+            .filter(|(_, stat)| stat.count >= min_samples)
+            .map(|(ngram, stat)| (ngram.as_str(), stat))
+            .collect();
+        ngrams.sort_by(|a, b| b.1.weakness().total_cmp(&a.1.weakness()).then(a.0.cmp(b.0)));
+        ngrams.truncate(count);
+        ngrams
+    }
+}
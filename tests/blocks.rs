@@ -0,0 +1,59 @@
+use moonlander_trainer::generators::blocks::BlockGenerator;
+use moonlander_trainer::generators::coding::create_tree_graph;
+use moonlander_trainer::generators::random;
+use moonlander_trainer::generators::simple::SingleStringGenerator;
+use moonlander_trainer::{GeneratorGraph, TypingPatternGenerator};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+fn block(
+    header: &str,
+    body: Arc<dyn TypingPatternGenerator>,
+    lines: &str,
+) -> Arc<dyn TypingPatternGenerator> {
+    Arc::new(
+        BlockGenerator::new(
+            "block",
+            Arc::new(SingleStringGenerator::new("header", header)),
+            body,
+            HashMap::from([
+                ("min_lines", lines.to_string()),
+                ("max_lines", lines.to_string()),
+            ]),
+        )
+        .unwrap(),
+    )
+}
+
+#[test]
+fn nested_blocks_are_indented_per_level() {
+    let inner = block(
+        "if (x)",
+        Arc::new(SingleStringGenerator::new("line", "y();")),
+        "1",
+    );
+    let outer = block("loop", inner, "2");
+    assert_eq!(
+        outer.generate().pattern,
+        "loop {\n\tif (x) {\n\t\ty();\n\t}\n\tif (x) {\n\t\ty();\n\t}\n}"
+    );
+}
+
+#[test]
+fn default_blocks_survive_snapshots() {
+    let graph = create_tree_graph().unwrap();
+    let loaded = GeneratorGraph::from_snapshot(&graph.snapshot().unwrap()).unwrap();
+    for name in ["if_block", "match_block"] {
+        random::seed(7);
+        let original = graph.find(name).unwrap().generate().pattern;
+        random::seed(7);
+        assert_eq!(loaded.find(name).unwrap().generate().pattern, original);
+
+        let lines: Vec<&str> = original.lines().collect();
+        assert!(lines[0].ends_with(" {"), "{}", original);
+        assert_eq!(*lines.last().unwrap(), "}");
+        assert!(lines[1..lines.len() - 1]
+            .iter()
+            .all(|l| l.starts_with('\t')));
+    }
+}
@@ -0,0 +1,31 @@
+use moonlander_trainer::generators::coding::create_tree_graph;
+use moonlander_trainer::generators::simple::SingleStringGenerator;
+use moonlander_trainer::{DrillSet, ExportFormat, GeneratorGraph};
+
+#[test]
+fn monkeytype_keeps_each_pattern_whole() {
+    let generator = SingleStringGenerator::new("call", "foo(a, b)");
+    let mut drills = DrillSet::generate(&generator, 2, Some(1));
+    drills.patterns[1].pattern = String::from("if x {\n\treturn y;\n}");
+    assert_eq!(
+        drills.export(ExportFormat::Monkeytype),
+        "foo(a, b)|if x { return y; }\n"
+    );
+}
+
+#[test]
+fn the_recorded_graph_generates_the_same_patterns() {
+    let graph = create_tree_graph().unwrap();
+    let generator = graph.find("number").unwrap();
+    let drills = DrillSet::generate(generator.as_ref(), 5, Some(7)).with_graph(&generator);
+    let recorded = GeneratorGraph::from_snapshot(drills.graph.as_ref().unwrap()).unwrap();
+    assert_eq!(recorded.root.name(), "number");
+    let again = DrillSet::generate(recorded.root.as_ref(), 5, Some(7));
+    let texts = |d: &DrillSet| {
+        d.patterns
+            .iter()
+            .map(|p| p.pattern.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(texts(&again), texts(&drills));
+}
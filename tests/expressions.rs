@@ -0,0 +1,148 @@
+use moonlander_trainer::generators::expressions::ExpressionGenerator;
+use moonlander_trainer::generators::random;
+use moonlander_trainer::generators::randomized::OneOfStringsPatternGenerator;
+use moonlander_trainer::{GeneratorError, TypingPatternGenerator};
+use std::iter::Peekable;
+use std::str::Chars;
+use std::sync::Arc;
+
+fn one_of(name: &'static str, strings: Vec<&'static str>) -> Arc<dyn TypingPatternGenerator> {
+    Arc::new(OneOfStringsPatternGenerator::new(name, strings).unwrap())
+}
+
+fn expressions(
+    members: Option<Arc<dyn TypingPatternGenerator>>,
+    config: &[(&'static str, &str)],
+) -> Result<ExpressionGenerator, GeneratorError> {
+    let config = config.iter().map(|(k, v)| (*k, v.to_string())).collect();
+    ExpressionGenerator::new(
+        "expression",
+        vec![one_of("operands", vec!["2", "3", "5"])],
+        members,
+        config,
+    )
+}
+
+/// Evaluates arithmetic with JavaScript's precedence: unary minus, then `* / %`, then `+ -`.
+fn evaluate(expression: &str) -> f64 {
+    fn primary(chars: &mut Peekable<Chars>) -> f64 {
+        skip_spaces(chars);
+        match chars.next() {
+            Some('-') => -primary(chars),
+            Some('(') => {
+                let value = binary(chars, 0);
+                skip_spaces(chars);
+                assert_eq!(chars.next(), Some(')'));
+                value
+            }
+            Some(c) => c.to_digit(10).expect("an operand") as f64,
+            None => panic!("unexpected end"),
+        }
+    }
+    fn binary(chars: &mut Peekable<Chars>, min_precedence: u8) -> f64 {
+        let mut value = primary(chars);
+        loop {
+            skip_spaces(chars);
+            let precedence = match chars.peek() {
+                Some('+' | '-') => 1,
+                Some('*' | '/' | '%') => 2,
+                _ => return value,
+            };
+            if precedence < min_precedence {
+                return value;
+            }
+            let operator = chars.next().unwrap();
+            // Left associative: the right side only takes tighter operators.
+            let right = binary(chars, precedence + 1);
+            value = match operator {
+                '+' => value + right,
+                '-' => value - right,
+                '*' => value * right,
+                '/' => value / right,
+                _ => value % right,
+            };
+        }
+    }
+    fn skip_spaces(chars: &mut Peekable<Chars>) {
+        while chars.peek() == Some(&' ') {
+            chars.next();
+        }
+    }
+    let mut chars = expression.chars().peekable();
+    let value = binary(&mut chars, 0);
+    assert_eq!(chars.next(), None, "trailing input in {}", expression);
+    value
+}
+
+#[test]
+fn minimal_parentheses_keep_the_meaning() {
+    let depths = [("min_depth", "1"), ("max_depth", "4")];
+    let minimal = expressions(None, &depths).unwrap();
+    let always = expressions(None, &[depths[0], depths[1], ("parenthesize", "always")]).unwrap();
+    for seed in 0..300 {
+        random::seed(seed);
+        let pattern = minimal.generate().pattern;
+        random::seed(seed);
+        let parenthesized = always.generate().pattern;
+        let (value, expected) = (evaluate(&pattern), evaluate(&parenthesized));
+        assert!(
+            value == expected || (value.is_nan() && expected.is_nan()),
+            "{} is {} but {} is {}",
+            pattern,
+            value,
+            parenthesized,
+            expected
+        );
+        assert!(!pattern.contains("--"), "{}", pattern);
+    }
+}
+
+#[test]
+fn all_groups_stay_balanced() {
+    let groups = "arithmetic,comparison,logical,bitwise,ternary,nullish,optional_chaining";
+    let generator = expressions(
+        Some(one_of("members", vec!["length", "name"])),
+        &[("groups", groups), ("max_depth", "4")],
+    )
+    .unwrap();
+    for seed in 0..300 {
+        random::seed(seed);
+        let pattern = generator.generate().pattern;
+        let mut depth = 0i32;
+        for c in pattern.chars() {
+            depth += match c {
+                '(' => 1,
+                ')' => -1,
+                _ => 0,
+            };
+            assert!(depth >= 0, "{}", pattern);
+        }
+        assert_eq!(depth, 0, "{}", pattern);
+        assert_eq!(
+            pattern.matches(" ? ").count(),
+            pattern.matches(" : ").count()
+        );
+        assert!(
+            pattern
+                .split("?.")
+                .skip(1)
+                .all(|rest| rest.starts_with("length") || rest.starts_with("name")),
+            "{}",
+            pattern
+        );
+    }
+}
+
+#[test]
+fn optional_chaining_needs_members() {
+    let generator = expressions(None, &[("groups", "arithmetic,optional_chaining")]).unwrap();
+    for seed in 0..100 {
+        random::seed(seed);
+        let pattern = generator.generate().pattern;
+        assert!(!pattern.contains("?."), "{}", pattern);
+    }
+    assert!(matches!(
+        expressions(None, &[("groups", "optional_chaining")]),
+        Err(GeneratorError::NoChoices { .. })
+    ));
+}
@@ -0,0 +1,216 @@
+//! Invariants of the generators, checked on random configurations. Every case seeds the
+//! generators' random number generator, so a failure proptest reports can be replayed exactly.
+
+use moonlander_trainer::generators::coding::{
+    create_tree_graph, NumberPatternGenerator, StringLiteralGenerator,
+};
+use moonlander_trainer::generators::random;
+use moonlander_trainer::generators::randomized::{
+    OneOfStringsPatternGenerator, WeightedPatternGenerator,
+};
+use moonlander_trainer::generators::sequences::{RandomRepeatGenerator, RepeatPatternGenerator};
+use moonlander_trainer::generators::simple::SingleStringGenerator;
+use moonlander_trainer::{GeneratorGraph, TypingPatternGenerator};
+use proptest::prelude::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+fn one_of(words: Vec<String>) -> Arc<dyn TypingPatternGenerator> {
+    Arc::new(OneOfStringsPatternGenerator {
+        name: String::from("words"),
+        strings: words,
+    })
+}
+
+fn words() -> impl Strategy<Value = Vec<String>> {
+    prop::collection::vec("[a-z]{1,8}", 1..10)
+}
+
+proptest! {
+    #[test]
+    fn random_repeat_count_is_in_range(
+        words in words(),
+        min_count in 0u32..5,
+        extra in 1u32..5,
+        seed: u64,
+    ) {
+        let max_count = min_count + extra;
+        let generator = RandomRepeatGenerator::new(
+            "repeat",
+            one_of(words),
+            HashMap::from([
+                ("min_count", min_count.to_string()),
+                ("max_count", max_count.to_string()),
+                ("delimiter", String::from(", ")),
+            ]),
+        )
+        .unwrap();
+        random::seed(seed);
+        let pattern = generator.generate();
+        let count = pattern.children.len() as u32;
+        prop_assert!((min_count..max_count).contains(&count), "{} items", count);
+        let items = if pattern.pattern.is_empty() {
+            0
+        } else {
+            pattern.pattern.split(", ").count() as u32
+        };
+        prop_assert_eq!(items, count);
+    }
+
+    #[test]
+    fn numbers_are_digits_of_a_length_in_range(
+        min_length in 1u32..10,
+        extra in 0u32..5,
+        seed: u64,
+    ) {
+        let max_length = min_length + extra;
+        let generator = NumberPatternGenerator::new(
+            "number",
+            HashMap::from([
+                ("min_length", min_length.to_string()),
+                ("max_length", max_length.to_string()),
+            ]),
+        )
+        .unwrap();
+        random::seed(seed);
+        let pattern = generator.generate().pattern;
+        prop_assert!(pattern.chars().all(|c| c.is_ascii_digit()), "{:?}", pattern);
+        let length = pattern.len() as u32;
+        prop_assert!((min_length..=max_length).contains(&length), "{:?}", pattern);
+    }
+
+    #[test]
+    fn weighted_choices_return_a_child(
+        weights in prop::collection::vec(0.01f32..10.0, 1..8),
+        seed: u64,
+    ) {
+        let children: Vec<(f32, Arc<dyn TypingPatternGenerator>)> = weights
+            .iter()
+            .enumerate()
+            .map(|(i, &weight)| {
+                let child = SingleStringGenerator::new(&format!("child{}", i), &i.to_string());
+                (weight, Arc::new(child) as Arc<dyn TypingPatternGenerator>)
+            })
+            .collect();
+        let generator = WeightedPatternGenerator::new("weighted", children).unwrap();
+        random::seed(seed);
+        for _ in 0..10 {
+            let pattern = generator.generate();
+            let i: usize = pattern.pattern.parse().unwrap();
+            prop_assert!(i < weights.len());
+            prop_assert_eq!(pattern.name, format!("child{}", i));
+        }
+    }
+
+    #[test]
+    fn camel_casing_keeps_the_length(words in words(), count in 0u32..6, seed: u64) {
+        let repeat = |camel_case: bool| {
+            let generator = RepeatPatternGenerator::new(
+                "repeat",
+                one_of(words.clone()),
+                HashMap::from([
+                    ("count", count.to_string()),
+                    ("delimiter", String::new()),
+                    ("camel_case_strings", camel_case.to_string()),
+                ]),
+            )
+            .unwrap();
+            random::seed(seed);
+            generator.generate().pattern
+        };
+        let plain = repeat(false);
+        let camel_cased = repeat(true);
+        prop_assert_eq!(plain.chars().count(), camel_cased.chars().count());
+        prop_assert_eq!(plain, camel_cased.to_lowercase());
+    }
+
+    #[test]
+    fn generate_into_matches_generate(seed: u64) {
+        let graph = create_tree_graph().unwrap();
+        for generator in graph.nodes().iter().chain(&graph.named) {
+            random::seed(seed);
+            let generated = generator.generate().pattern;
+            random::seed(seed);
+            let mut pattern = String::new();
+            generator.generate_into(&mut pattern);
+            prop_assert_eq!(generated, pattern, "{}", generator.name());
+        }
+    }
+
+    #[test]
+    fn snapshots_generate_the_same_patterns(seed: u64) {
+        let graph = create_tree_graph().unwrap();
+        let loaded = GeneratorGraph::from_snapshot(&graph.snapshot().unwrap()).unwrap();
+        random::seed(seed);
+        let original = graph.root.generate().pattern;
+        random::seed(seed);
+        prop_assert_eq!(original, loaded.root.generate().pattern);
+    }
+}
+
+#[test]
+fn snapshots_keep_shared_generators_shared() {
+    let graph = create_tree_graph().unwrap();
+    let snapshot = graph.snapshot().unwrap();
+    let loaded = GeneratorGraph::from_snapshot(&snapshot).unwrap();
+    assert_eq!(loaded.nodes().len(), graph.nodes().len());
+    assert_eq!(loaded.snapshot().unwrap(), snapshot);
+}
+
+#[test]
+fn enumeration_yields_cardinality_patterns() {
+    let words = one_of(vec![
+        String::from("a"),
+        String::from("b"),
+        String::from("c"),
+    ]);
+    let generator: Arc<dyn TypingPatternGenerator> = Arc::new(
+        RepeatPatternGenerator::new(
+            "repeat",
+            words,
+            HashMap::from([("count", String::from("3"))]),
+        )
+        .unwrap(),
+    );
+    let graph = GeneratorGraph::new(generator, Vec::new());
+    assert_eq!(graph.cardinality(), Some(27));
+    let patterns: Vec<String> = graph.enumerate().unwrap().collect();
+    assert_eq!(patterns.len(), 27);
+    assert_eq!(patterns[0], "a a a");
+    assert_eq!(patterns[26], "c c c");
+}
+
+#[test]
+fn scientific_numbers_are_normalized() {
+    let generator = NumberPatternGenerator::new(
+        "number",
+        HashMap::from([("format", String::from("scientific"))]),
+    )
+    .unwrap();
+    for seed in 0..200 {
+        random::seed(seed);
+        let pattern = generator.generate().pattern;
+        let mantissa = pattern.trim_start_matches('-');
+        assert!(
+            matches!(mantissa.chars().next(), Some('1'..='9')),
+            "{}",
+            pattern
+        );
+    }
+}
+
+#[test]
+fn template_literals_escape_accidental_interpolations() {
+    let generator = StringLiteralGenerator::new(
+        "string",
+        Arc::new(SingleStringGenerator::new("content", "cost ${price}")),
+        None,
+        HashMap::from([
+            ("styles", String::from("backtick")),
+            ("escape_probability", String::from("0")),
+            ("interpolation_probability", String::from("0")),
+        ]),
+    )
+    .unwrap();
+    assert_eq!(generator.generate().pattern, "`cost \\${price}`");
+}
@@ -0,0 +1,177 @@
+use moonlander_trainer::generators::coding::create_tree_graph;
+use moonlander_trainer::generators::graph::Severity;
+use moonlander_trainer::generators::randomized::WeightedPatternGenerator;
+use moonlander_trainer::generators::regex::RegexPatternGenerator;
+use moonlander_trainer::generators::simple::{ListOfPatternsGenerator, SingleStringGenerator};
+use moonlander_trainer::generators::snapshot::GeneratorSnapshot;
+use moonlander_trainer::{GeneratorError, GeneratorGraph, TypingPatternGenerator};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+fn single(name: &str, pattern: &str) -> Arc<dyn TypingPatternGenerator> {
+    Arc::new(SingleStringGenerator::new(name, pattern))
+}
+
+fn weighted(weights: &[f32]) -> Result<WeightedPatternGenerator, GeneratorError> {
+    let children = weights
+        .iter()
+        .enumerate()
+        .map(|(i, &weight)| (weight, single(&format!("child{}", i), &i.to_string())))
+        .collect();
+    WeightedPatternGenerator::new("weighted", children)
+}
+
+#[test]
+fn zero_weights_are_warned_about_but_load() {
+    let generator: Arc<dyn TypingPatternGenerator> = Arc::new(weighted(&[1.0, 0.0]).unwrap());
+    let graph = GeneratorGraph::new(generator, Vec::new());
+    let loaded = GeneratorGraph::from_snapshot(&graph.snapshot().unwrap()).unwrap();
+    let lints = loaded.check();
+    assert_eq!(lints.len(), 1, "{:?}", lints);
+    assert_eq!(lints[0].severity, Severity::Warning);
+    assert!(lints[0].message.contains("child1"), "{}", lints[0]);
+    // The child that is never chosen isn't counted either.
+    assert_eq!(loaded.cardinality(), Some(1));
+    assert_eq!(loaded.enumerate().unwrap().collect::<Vec<_>>(), ["0"]);
+}
+
+#[test]
+fn invalid_weights_are_rejected() {
+    assert!(matches!(
+        weighted(&[0.0, 0.0]),
+        Err(GeneratorError::NoChoices { .. })
+    ));
+    for weights in [[1.0, -1.0], [1.0, f32::NAN], [f32::INFINITY, 1.0]] {
+        assert!(matches!(
+            weighted(&weights),
+            Err(GeneratorError::InvalidOption { .. })
+        ));
+    }
+}
+
+#[test]
+fn duplicate_names_are_reported() {
+    let children = vec![(1.0, single("same", "a")), (1.0, single("same", "b"))];
+    let generator = Arc::new(WeightedPatternGenerator::new("weighted", children).unwrap());
+    // A shared generator is one generator, however often it is reached.
+    let graph = GeneratorGraph::new(generator.clone(), vec![generator]);
+    let lints = graph.check();
+    assert_eq!(lints.len(), 1, "{:?}", lints);
+    assert_eq!(lints[0].generator, "same");
+
+    let lints = create_tree_graph().unwrap().check();
+    assert!(
+        lints
+            .iter()
+            .all(|lint| !lint.message.contains("is the name of")),
+        "{:?}",
+        lints
+    );
+}
+
+#[test]
+fn cycles_are_not_saved() {
+    let weighted = Arc::new(RwLock::new(weighted(&[1.0]).unwrap()));
+    {
+        let mut generator = weighted.write().unwrap();
+        generator.patterns.push((1.0, weighted.clone()));
+        generator.total_weight = 2.0;
+    }
+    let graph = GeneratorGraph::new(weighted.clone(), Vec::new());
+    match graph.snapshot() {
+        Err(GeneratorError::InvalidGraph { reason, .. }) => {
+            assert!(reason.contains("weighted -> weighted"), "{}", reason)
+        }
+        other => panic!("{:?}", other),
+    }
+    // Break the cycle, so the Arcs are dropped.
+    weighted.write().unwrap().patterns.pop();
+}
+
+#[test]
+fn every_unsupported_generator_is_reported() {
+    let regex = |name: &'static str| -> Arc<dyn TypingPatternGenerator> {
+        Arc::new(
+            RegexPatternGenerator::new(name, HashMap::from([("regex", String::from("[a-z]+"))]))
+                .unwrap(),
+        )
+    };
+    let list = ListOfPatternsGenerator::new(
+        "list",
+        vec![regex("first"), single("plain", "x"), regex("second")],
+        HashMap::new(),
+    )
+    .unwrap();
+    let graph = GeneratorGraph::new(Arc::new(list), Vec::new());
+    match graph.snapshot() {
+        Err(GeneratorError::InvalidGraph { reason, .. }) => assert_eq!(
+            reason,
+            "can't serialize first (RegexPatternGenerator), second (RegexPatternGenerator)"
+        ),
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn invalid_snapshots_of_leaf_generators_are_rejected() {
+    let snapshot = create_tree_graph().unwrap().snapshot().unwrap();
+    let broken = |edit: &dyn Fn(&mut GeneratorSnapshot) -> bool| {
+        let mut snapshot = snapshot.clone();
+        let edited = snapshot
+            .generators
+            .iter_mut()
+            .map(edit)
+            .filter(|&edited| edited)
+            .count();
+        assert!(edited > 0);
+        GeneratorGraph::from_snapshot(&snapshot)
+    };
+    let result = broken(&|generator| match generator {
+        GeneratorSnapshot::StringLiteral { styles, .. } => {
+            styles.clear();
+            true
+        }
+        _ => false,
+    });
+    assert!(
+        matches!(result, Err(GeneratorError::NoChoices { .. })),
+        "{:?}",
+        result
+    );
+    let result = broken(&|generator| match generator {
+        GeneratorSnapshot::StringLiteral {
+            escape_probability, ..
+        } => {
+            *escape_probability = 1.5;
+            true
+        }
+        _ => false,
+    });
+    assert!(
+        matches!(result, Err(GeneratorError::InvalidOption { .. })),
+        "{:?}",
+        result
+    );
+    for probability in ["content", "terminator"] {
+        let result = broken(&|generator| match generator {
+            GeneratorSnapshot::BalancedNesting {
+                content_probability,
+                terminator_probability,
+                ..
+            } => {
+                match probability {
+                    "content" => *content_probability = 1.5,
+                    _ => *terminator_probability = -0.5,
+                }
+                true
+            }
+            _ => false,
+        });
+        match result {
+            Err(GeneratorError::InvalidOption { option, .. }) => {
+                assert_eq!(option, format!("{}_probability", probability))
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use moonlander_trainer::generators::markov::{MarkovModel, MarkovPatternGenerator, Tokenization};
+use moonlander_trainer::generators::random;
+use moonlander_trainer::{GeneratorError, TypingPatternGenerator};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+fn temp_file(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "moonlander-trainer-{}-{}",
+        std::process::id(),
+        name
+    ))
+}
+
+fn next_tokens(model: &MarkovModel, state: &str) -> Vec<String> {
+    model.transitions[state].keys().cloned().collect()
+}
+
+#[test]
+fn tokens_are_identifiers_numbers_and_symbols() {
+    let mut model = MarkovModel::new(1, Tokenization::Tokens);
+    model.train("let foo_1 =   3.5;\n\n  ");
+    // The empty state starts a line and the empty token ends it.
+    assert_eq!(next_tokens(&model, ""), ["let"]);
+    assert_eq!(next_tokens(&model, "let"), [" "]);
+    assert_eq!(next_tokens(&model, " "), ["3.5", "=", "foo_1"]);
+    assert_eq!(next_tokens(&model, ";"), [""]);
+    // Blank lines aren't trained on, so they don't end a line right away.
+    assert_eq!(model.transitions[""].get(""), None);
+}
+
+#[test]
+fn chars_are_counted() {
+    let mut model = MarkovModel::new(1, Tokenization::Chars);
+    model.train("aab\nab");
+    assert_eq!(model.transitions[""]["a"], 2);
+    assert_eq!(model.transitions["a"]["a"], 1);
+    assert_eq!(model.transitions["a"]["b"], 2);
+    assert_eq!(model.transitions["b"][""], 2);
+}
+
+#[test]
+fn a_single_line_is_generated_again() {
+    let mut model = MarkovModel::new(3, Tokenization::Chars);
+    model.train("foo.bar(baz);");
+    let generator = MarkovPatternGenerator::new(
+        "markov",
+        Arc::new(model),
+        HashMap::from([("min_length", String::from("1"))]),
+    )
+    .unwrap();
+    random::seed(1);
+    assert_eq!(generator.generate().pattern, "foo.bar(baz);");
+}
+
+#[test]
+fn models_are_saved_and_loaded() {
+    let mut model = MarkovModel::new(2, Tokenization::Tokens);
+    model.train("fn main() {}\nlet x = y;");
+    let path = temp_file("model.json");
+    model.save(&path).unwrap();
+    let loaded = MarkovModel::load(&path);
+    fs::remove_file(&path).unwrap();
+    let loaded = loaded.unwrap();
+    assert_eq!(loaded.order, 2);
+    assert_eq!(loaded.tokenization, Tokenization::Tokens);
+    assert_eq!(loaded.transitions, model.transitions);
+}
+
+#[test]
+fn models_without_next_tokens_are_rejected() {
+    for transitions in [r#"{"": {}}"#, r#"{"": {"a": 0}}"#] {
+        let path = temp_file("broken.json");
+        let json = format!(
+            r#"{{"order": 1, "tokenization": "Chars", "transitions": {}}}"#,
+            transitions
+        );
+        fs::write(&path, json).unwrap();
+        let loaded = MarkovModel::load(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(loaded.is_err(), "{} was loaded", transitions);
+    }
+
+    let mut model = MarkovModel::new(1, Tokenization::Chars);
+    model.transitions.insert(String::new(), Default::default());
+    let generator = MarkovPatternGenerator::new("markov", Arc::new(model), HashMap::new()).unwrap();
+    assert!(matches!(
+        generator.check(),
+        Err(GeneratorError::NoChoices { .. })
+    ));
+    // Generating from it anyway gives an empty pattern instead of panicking.
+    assert_eq!(generator.generate().pattern, "");
+}
@@ -0,0 +1,87 @@
+use moonlander_trainer::generators::nesting::BalancedNestingGenerator;
+use moonlander_trainer::generators::random;
+use moonlander_trainer::generators::simple::SingleStringGenerator;
+use moonlander_trainer::TypingPatternGenerator;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const PAIRS: [(char, char); 4] = [('(', ')'), ('[', ']'), ('{', '}'), ('<', '>')];
+
+/// Checks that every opener is closed by its closer, returning the deepest nesting.
+fn depth(pattern: &str) -> usize {
+    let mut stack = Vec::new();
+    let mut deepest = 0;
+    for c in pattern.chars() {
+        if let Some(&(_, close)) = PAIRS.iter().find(|(open, _)| *open == c) {
+            stack.push(close);
+            deepest = deepest.max(stack.len());
+        } else if PAIRS.iter().any(|(_, close)| *close == c) {
+            assert_eq!(stack.pop(), Some(c), "{}", pattern);
+        }
+    }
+    assert!(stack.is_empty(), "{}", pattern);
+    deepest
+}
+
+fn generator(content: bool, options: &[(&'static str, &str)]) -> BalancedNestingGenerator {
+    let content = content
+        .then(|| Arc::new(SingleStringGenerator::new("x", "x")) as Arc<dyn TypingPatternGenerator>);
+    let mut config: HashMap<&str, String> = options
+        .iter()
+        .map(|(key, value)| (*key, value.to_string()))
+        .collect();
+    config.insert("brackets", String::from("()[]{}<>"));
+    BalancedNestingGenerator::new("nesting", content, config).unwrap()
+}
+
+#[test]
+fn nestings_are_balanced_and_within_the_depth() {
+    for content in [false, true] {
+        for (min_depth, max_depth) in [(1, 1), (2, 4), (3, 3)] {
+            let generator = generator(
+                content,
+                &[
+                    ("min_depth", &min_depth.to_string()),
+                    ("max_depth", &max_depth.to_string()),
+                    ("max_siblings", "3"),
+                ],
+            );
+            for seed in 0..50 {
+                random::seed(seed);
+                let pattern = generator.generate().pattern;
+                let depth = depth(&pattern);
+                assert!((min_depth..=max_depth).contains(&depth), "{}", pattern);
+                assert!(pattern.chars().count() >= generator.min_length(&[]));
+                if !content {
+                    assert!(!pattern.contains('x'), "{}", pattern);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn closing_runs_close_within_the_depth() {
+    let generator = generator(
+        false,
+        &[
+            ("min_depth", "2"),
+            ("max_depth", "4"),
+            ("closing_runs", "true"),
+            ("terminator_probability", "1"),
+        ],
+    );
+    for seed in 0..50 {
+        random::seed(seed);
+        let pattern = generator.generate().pattern;
+        let closers = pattern.strip_suffix(';').unwrap();
+        assert!((2..=4).contains(&closers.chars().count()), "{}", pattern);
+        assert!(
+            closers
+                .chars()
+                .all(|c| PAIRS.iter().any(|(_, close)| *close == c)),
+            "{}",
+            pattern
+        );
+    }
+}
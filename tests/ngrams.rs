@@ -0,0 +1,155 @@
+use moonlander_trainer::generators::random;
+use moonlander_trainer::generators::weak_ngrams::WeakNgramGenerator;
+use moonlander_trainer::session::log::{KeystrokeEvent, SessionLog};
+use moonlander_trainer::session::ngrams::NgramStats;
+use moonlander_trainer::TypingPatternGenerator;
+use std::collections::HashMap;
+
+/// Logs typing `text` correctly, `ms` per keystroke, starting at `position`.
+fn typed(log: &mut SessionLog, text: &str, position: usize, ms: u64) {
+    for (i, c) in text.chars().enumerate() {
+        keystroke(log, position + i, c, c, ms);
+    }
+}
+
+fn keystroke(log: &mut SessionLog, position: usize, expected: char, typed: char, ms: u64) {
+    log.events.push(KeystrokeEvent {
+        pattern: String::from("drill"),
+        position,
+        expected,
+        typed,
+        elapsed_ms: ms,
+    });
+}
+
+#[test]
+fn ngrams_are_counted_without_the_first_keystroke() {
+    let mut log = SessionLog::default();
+    // The first keystroke of a pattern waits for the reader, so its time isn't counted.
+    keystroke(&mut log, 0, 'a', 'a', 5000);
+    typed(&mut log, "bab", 1, 100);
+    let stats = NgramStats::from_log(&log, &[2, 3]);
+    let ab = &stats.ngrams["ab"];
+    assert_eq!((ab.count, ab.total_ms, ab.errors), (2, 200, 0));
+    assert_eq!(stats.ngrams["ba"].count, 1);
+    assert_eq!(stats.ngrams["aba"].total_ms, 200);
+    assert_eq!(stats.ngrams["bab"].mean_ms(), 200.0);
+    assert_eq!(stats.ngrams.len(), 4);
+}
+
+#[test]
+fn ngrams_longer_than_the_pattern_and_across_patterns_are_not_counted() {
+    let mut log = SessionLog::default();
+    typed(&mut log, "ab", 0, 100);
+    typed(&mut log, "cd", 0, 100);
+    let stats = NgramStats::from_log(&log, &[1, 2, 3]);
+    let mut ngrams: Vec<&str> = stats.ngrams.keys().map(String::as_str).collect();
+    ngrams.sort();
+    assert_eq!(ngrams, ["ab", "cd"]);
+}
+
+#[test]
+fn mistakes_count_against_the_intended_character() {
+    let mut log = SessionLog::default();
+    typed(&mut log, "ab", 0, 100);
+    keystroke(&mut log, 2, 'c', 'x', 150);
+    keystroke(&mut log, 2, 'c', 'v', 150);
+    keystroke(&mut log, 2, 'c', 'c', 100);
+    typed(&mut log, "d", 3, 100);
+    let stats = NgramStats::from_log(&log, &[2]);
+    let bc = &stats.ngrams["bc"];
+    assert_eq!((bc.count, bc.total_ms, bc.errors), (1, 400, 1));
+    assert_eq!(stats.ngrams["cd"].errors, 1);
+    assert_eq!(stats.ngrams["ab"].errors, 0);
+    assert!(stats.ngrams["cd"].error_rate() == 1.0);
+}
+
+#[test]
+fn backspaced_characters_are_forgotten() {
+    let mut log = SessionLog::default();
+    // "abc", two backspaces, then "bc" again.
+    typed(&mut log, "abc", 0, 100);
+    typed(&mut log, "bc", 1, 100);
+    let stats = NgramStats::from_log(&log, &[2]);
+    assert_eq!(stats.ngrams.get("cb"), None);
+    assert_eq!(stats.ngrams["ab"].count, 2);
+    assert_eq!(stats.ngrams["bc"].count, 2);
+}
+
+#[test]
+fn weakest_orders_by_time_and_errors() {
+    let mut log = SessionLog::default();
+    for _ in 0..3 {
+        keystroke(&mut log, 0, 'a', 'a', 100);
+        keystroke(&mut log, 1, 'b', 'b', 100);
+        keystroke(&mut log, 2, 'c', 'c', 300);
+        keystroke(&mut log, 3, 'd', 'x', 50);
+        keystroke(&mut log, 3, 'd', 'd', 50);
+    }
+    keystroke(&mut log, 0, 'z', 'z', 100);
+    keystroke(&mut log, 1, 'y', 'y', 900);
+    let stats = NgramStats::from_log(&log, &[2]);
+    // "bc" is slowest, "cd" is quick but always mistyped and "zy" has too few samples.
+    let weakest: Vec<&str> = stats
+        .weakest(3, 2)
+        .into_iter()
+        .map(|(ngram, _)| ngram)
+        .collect();
+    assert_eq!(weakest, ["bc", "cd", "ab"]);
+    assert_eq!(stats.weakest(1, 1)[0].0, "zy");
+}
+
+#[test]
+fn weak_ngrams_are_drilled_in_words() {
+    let mut log = SessionLog::default();
+    for _ in 0..3 {
+        keystroke(&mut log, 0, 'q', 'q', 100);
+        keystroke(&mut log, 1, 'u', 'u', 800);
+        keystroke(&mut log, 2, '(', '(', 100);
+        keystroke(&mut log, 3, '0', '0', 900);
+    }
+    let stats = NgramStats::from_log(&log, &[2]);
+    let words = vec![
+        String::from("queue"),
+        String::from("quit"),
+        String::from("foo"),
+    ];
+    let generator = WeakNgramGenerator::new(
+        "weak",
+        &stats,
+        &words,
+        HashMap::from([("ngram_count", String::from("2"))]),
+    )
+    .unwrap();
+    assert_eq!(generator.targets[0], (String::from("(0"), Vec::new()));
+    assert_eq!(generator.targets[1].0, "qu");
+    assert_eq!(generator.targets[1].1, ["queue", "quit"]);
+    random::seed(1);
+    let pattern = generator.generate().pattern;
+    assert_eq!(pattern.split(' ').count(), 6, "{}", pattern);
+    // Without a word containing it, "(0" is drilled on its own.
+    assert!(
+        pattern
+            .split(' ')
+            .all(|word| ["(0", "queue", "quit"].contains(&word)),
+        "{}",
+        pattern
+    );
+}
+
+#[test]
+fn logs_are_appended_and_loaded() {
+    let path = std::env::temp_dir().join(format!("moonlander-trainer-{}-log", std::process::id()));
+    let mut first = SessionLog::default();
+    typed(&mut first, "ab", 0, 100);
+    let mut second = SessionLog::default();
+    keystroke(&mut second, 0, 'c', 'x', 100);
+    first.save(&path).unwrap();
+    second.append_to(&path).unwrap();
+    let loaded = SessionLog::load(&path);
+    std::fs::remove_file(&path).unwrap();
+    let loaded = loaded.unwrap();
+    assert_eq!(loaded.events.len(), 3);
+    assert_eq!(loaded.events[..2], first.events[..]);
+    assert!(loaded.events[2].is_error());
+}
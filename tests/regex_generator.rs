@@ -0,0 +1,56 @@
+use moonlander_trainer::generators::random;
+use moonlander_trainer::generators::regex::RegexPatternGenerator;
+use moonlander_trainer::{GeneratorError, TypingPatternGenerator};
+use regex::Regex;
+use std::collections::HashMap;
+
+fn generator(regex: &str) -> Result<RegexPatternGenerator, GeneratorError> {
+    RegexPatternGenerator::new("regex", HashMap::from([("regex", regex.to_string())]))
+}
+
+#[test]
+fn patterns_match_the_regex() {
+    for source in [
+        r"[a-z]+_[0-9]{2,4}",
+        r"(foo|bar)\.(baz)*\(\)",
+        r"\w+\s?\d*",
+        r"[^a-z]{3}",
+        r"^0x[0-9A-F]{1,8}$",
+        r".+",
+    ] {
+        let generator = generator(source).unwrap();
+        let regex = Regex::new(&format!("^(?:{})$", source)).unwrap();
+        for seed in 0..50 {
+            random::seed(seed);
+            let pattern = generator.generate().pattern;
+            assert!(regex.is_match(&pattern), "{:?} gave {:?}", source, pattern);
+            assert!(pattern.chars().count() >= generator.min_length(&[]));
+        }
+    }
+}
+
+#[test]
+fn unbounded_repeats_are_capped() {
+    let generator = RegexPatternGenerator::new(
+        "regex",
+        HashMap::from([
+            ("regex", String::from("a*")),
+            ("max_repeat", String::from("3")),
+        ]),
+    )
+    .unwrap();
+    for seed in 0..50 {
+        random::seed(seed);
+        assert!(generator.generate().pattern.len() <= 3);
+    }
+}
+
+#[test]
+fn invalid_regexes_are_rejected() {
+    for source in ["[a-", "(?=a)b", "(?<!a)b", r"[^\s\S]"] {
+        match generator(source) {
+            Err(GeneratorError::InvalidOption { option, .. }) => assert_eq!(option, "regex"),
+            other => panic!("{:?} gave {:?}", source, other.map(|g| g.regex)),
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use moonlander_trainer::generators::random;
+use moonlander_trainer::generators::randomized::OneOfStringsPatternGenerator;
+use moonlander_trainer::generators::script::ScriptGenerator;
+use moonlander_trainer::{GeneratorError, GeneratorGraph, TypingPatternGenerator};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const SIGNATURE: &str = r#"
+let params = [];
+for i in 0..random(1, 3) {
+    params.push(generate("symbols") + ": " + choose(["i32", "String", "&str"]));
+}
+"fn " + generate("symbols") + "(" + join(params, ", ") + ")"
+"#;
+
+fn script(script: &str) -> Result<ScriptGenerator, GeneratorError> {
+    let symbols: Arc<dyn TypingPatternGenerator> =
+        Arc::new(OneOfStringsPatternGenerator::new("symbols", vec!["foo", "bar", "baz"]).unwrap());
+    ScriptGenerator::new(
+        "script",
+        vec![symbols],
+        HashMap::from([("script", script.to_string())]),
+    )
+}
+
+#[test]
+fn scripts_call_named_generators() {
+    let generator = script(SIGNATURE).unwrap();
+    for seed in 0..20 {
+        random::seed(seed);
+        let pattern = generator.generate();
+        assert!(pattern.pattern.starts_with("fn "), "{}", pattern.pattern);
+        let params = pattern.pattern.matches(':').count();
+        assert!((1..=3).contains(&params), "{}", pattern.pattern);
+        // Every call of generate() is recorded, the name last.
+        assert_eq!(pattern.children.len(), params + 1);
+        assert!(pattern.children.iter().all(|c| c.name == "symbols"));
+
+        random::seed(seed);
+        assert_eq!(generator.generate().pattern, pattern.pattern);
+    }
+}
+
+#[test]
+fn scripts_can_return_arrays() {
+    let generator = script(r#"[generate("symbols"), "(", 1, ")"]"#).unwrap();
+    let pattern = generator.generate().pattern;
+    assert!(pattern.ends_with("(1)"), "{}", pattern);
+}
+
+#[test]
+fn failing_scripts_are_rejected() {
+    for source in ["let x = ;", r#"generate("missing")"#, "loop {}", "42"] {
+        match script(source) {
+            Err(GeneratorError::Script { generator, .. }) => assert_eq!(generator, "script"),
+            other => panic!("{:?} gave {:?}", source, other.map(|g| g.script)),
+        }
+    }
+}
+
+#[test]
+fn scripts_are_part_of_snapshots() {
+    let generator: Arc<dyn TypingPatternGenerator> = Arc::new(script(SIGNATURE).unwrap());
+    let graph = GeneratorGraph::new(generator, Vec::new());
+    let loaded = GeneratorGraph::from_snapshot(&graph.snapshot().unwrap()).unwrap();
+    random::seed(1);
+    let original = graph.root.generate().pattern;
+    random::seed(1);
+    assert_eq!(loaded.root.generate().pattern, original);
+}
+
+#[test]
+fn unknown_functions_are_rejected_in_branches_not_taken() {
+    for source in [
+        r#"if random(0, 1) > 1 { rand(0, 9) } else { "x" }"#,
+        r#"if random(0, 1) > 1 { generate("missing") } else { "x" }"#,
+    ] {
+        match script(source) {
+            Err(GeneratorError::Script { reason, .. }) => assert!(
+                reason.contains("rand") || reason.contains("missing"),
+                "{}",
+                reason
+            ),
+            other => panic!("{:?} gave {:?}", source, other.map(|g| g.script)),
+        }
+    }
+    // Functions the script defines, methods and Rhai's own keywords are fine.
+    let source = r#"fn twice(s) { s + s } let a = [1]; a.push(2); twice(type_of(a.len()))"#;
+    assert_eq!(script(source).unwrap().generate().pattern, "i64i64");
+}
+
+#[test]
+fn runtime_errors_give_empty_patterns() {
+    // Compiled without the trial run, which could take the failing branch.
+    let source = r#"if chance(0.5) { random(2, 1).to_string() } else { "ok" }"#;
+    let generator =
+        ScriptGenerator::from_script("script", source.to_string(), Vec::new(), 1000).unwrap();
+    let mut failed = 0;
+    for seed in 0..20 {
+        random::seed(seed);
+        let mut pattern = String::new();
+        generator.generate_into(&mut pattern);
+        if pattern.is_empty() {
+            failed += 1;
+            let error = generator.last_error().unwrap();
+            assert!(error.contains("empty range"), "{}", error);
+        } else {
+            assert_eq!(pattern, "ok");
+        }
+    }
+    assert!(failed > 0);
+}
@@ -0,0 +1,105 @@
+use moonlander_trainer::generators::simple::SingleStringGenerator;
+use moonlander_trainer::session::{Indentation, Key};
+use moonlander_trainer::{KeyResult, SessionConfig, TypingPatternGenerator, TypingSession};
+use std::sync::Arc;
+
+fn single(name: &str, pattern: &str) -> Arc<dyn TypingPatternGenerator> {
+    Arc::new(SingleStringGenerator::new(name, pattern))
+}
+
+#[test]
+fn replacing_the_generator_keeps_the_drill_and_statistics() {
+    let mut session = TypingSession::new(single("old", "ab"), SessionConfig::default());
+    assert_eq!(session.type_char('a', 0), KeyResult::Correct);
+    assert_eq!(session.type_char('x', 10), KeyResult::Mistyped);
+
+    session.set_generator(single("new", "cd"));
+    assert_eq!(session.drill().name, "old");
+    assert_eq!(session.position(), 1);
+    assert_eq!(session.stats.errors, 1);
+
+    assert_eq!(session.type_char('b', 10), KeyResult::Completed);
+    assert_eq!(session.drill().name, "new");
+    assert_eq!(session.drill().text(), "cd");
+    assert_eq!(session.stats.completed, 1);
+    assert_eq!(session.stats.keystrokes, 3);
+}
+
+fn drill_text(pattern: &str, config: SessionConfig) -> (String, Vec<Key>) {
+    let session = TypingSession::new(single("drill", pattern), config);
+    (session.drill().text(), session.drill().expected_keys())
+}
+
+#[test]
+fn indentation_is_rendered_and_auto_indented() {
+    let config = SessionConfig {
+        indentation: Indentation::Spaces(2),
+        ..SessionConfig::default()
+    };
+    let (text, keys) = drill_text("a {\n\tb\n}", config.clone());
+    assert_eq!(text, "a {\n  b\n}");
+    assert_eq!(keys.len(), text.chars().count());
+
+    let (text, keys) = drill_text(
+        "a {\n\tb\n}",
+        SessionConfig {
+            auto_indent: true,
+            ..config
+        },
+    );
+    assert_eq!(text, "a {\n  b\n}");
+    // The editor types the two spaces.
+    assert_eq!(keys.len(), text.chars().count() - 2);
+}
+
+#[test]
+fn backspace_after_a_mistake_only_clears_it() {
+    let mut session = TypingSession::new(single("drill", "abc"), SessionConfig::default());
+    session.type_char('a', 0);
+    session.type_char('b', 0);
+    assert_eq!(session.type_char('x', 0), KeyResult::Mistyped);
+    assert!(session.mistyped());
+
+    session.press(Key::Backspace, 0);
+    assert!(!session.mistyped());
+    assert_eq!(session.position(), 2);
+
+    session.press(Key::Backspace, 0);
+    assert_eq!(session.position(), 1);
+}
+
+fn auto_paired(pattern: &str) -> TypingSession {
+    let config = SessionConfig {
+        auto_pair: true,
+        ..SessionConfig::default()
+    };
+    TypingSession::new(single("drill", pattern), config)
+}
+
+#[test]
+fn apostrophes_are_not_auto_paired() {
+    let session = auto_paired("don't say('hi')");
+    let openers: Vec<Option<usize>> = session.drill().chars.iter().map(|c| c.opener).collect();
+    // The quotes around `hi` and the parentheses pair up, the apostrophe in `don't` is plain.
+    assert_eq!(openers[3], None);
+    assert_eq!(openers[13], Some(10));
+    assert_eq!(openers[14], Some(9));
+}
+
+#[test]
+fn auto_closed_characters_are_typed_over_or_skipped() {
+    let mut session = auto_paired("f(x)");
+    assert_eq!(session.type_char('f', 0), KeyResult::Correct);
+    assert_eq!(session.type_char('(', 0), KeyResult::Correct);
+    assert_eq!(session.pending_closers(), vec![3]);
+    assert_eq!(session.type_char('x', 0), KeyResult::Correct);
+    assert_eq!(session.press(Key::Skip, 0), KeyResult::Completed);
+
+    // Backspace over an opener removes the closer with it.
+    let mut session = auto_paired("f(x)");
+    session.type_char('f', 0);
+    session.type_char('(', 0);
+    session.press(Key::Backspace, 0);
+    assert_eq!(session.position(), 1);
+    assert!(session.pending_closers().is_empty());
+}
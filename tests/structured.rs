@@ -0,0 +1,208 @@
+use moonlander_trainer::generators::random;
+use moonlander_trainer::generators::structured::{
+    DateTimeKind, DateTimePatternGenerator, DurationPatternGenerator, HexColorPatternGenerator,
+    IpAddressPatternGenerator, PortPatternGenerator, SemverPatternGenerator, UuidPatternGenerator,
+};
+use moonlander_trainer::{GeneratorError, TypingPatternGenerator};
+use regex::Regex;
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+
+fn config(options: &[(&'static str, &str)]) -> HashMap<&'static str, String> {
+    options
+        .iter()
+        .map(|(key, value)| (*key, value.to_string()))
+        .collect()
+}
+
+/// Patterns of `generator` for several seeds.
+fn patterns(generator: &dyn TypingPatternGenerator) -> Vec<String> {
+    (0..100)
+        .map(|seed| {
+            random::seed(seed);
+            generator.generate().pattern
+        })
+        .collect()
+}
+
+fn assert_all_match(generator: &dyn TypingPatternGenerator, regex: &str) {
+    let regex = Regex::new(regex).unwrap();
+    for pattern in patterns(generator) {
+        assert!(
+            regex.is_match(&pattern),
+            "{:?} doesn't match {}",
+            pattern,
+            regex
+        );
+    }
+}
+
+#[test]
+fn uuids_are_version_4() {
+    let generator = UuidPatternGenerator::new("uuid", HashMap::new()).unwrap();
+    assert_all_match(
+        &generator,
+        "^[0-9a-f]{8}-[0-9a-f]{4}-4[0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}$",
+    );
+    let generator = UuidPatternGenerator::new("uuid", config(&[("uppercase", "true")])).unwrap();
+    assert_all_match(
+        &generator,
+        "^[0-9A-F]{8}-[0-9A-F]{4}-4[0-9A-F]{3}-[89AB][0-9A-F]{3}-[0-9A-F]{12}$",
+    );
+}
+
+#[test]
+fn ip_addresses_parse() {
+    let generator =
+        IpAddressPatternGenerator::new("ip", config(&[("ipv6_probability", "0")])).unwrap();
+    for pattern in patterns(&generator) {
+        let octets: Vec<u32> = pattern.split('.').map(|o| o.parse().unwrap()).collect();
+        assert_eq!(octets.len(), 4, "{}", pattern);
+        assert!(octets.iter().all(|&octet| octet <= 255), "{}", pattern);
+    }
+    let generator =
+        IpAddressPatternGenerator::new("ip", config(&[("ipv6_probability", "1")])).unwrap();
+    for pattern in patterns(&generator) {
+        assert!(pattern.parse::<Ipv6Addr>().is_ok(), "{}", pattern);
+    }
+    let generator = IpAddressPatternGenerator::new(
+        "ip",
+        config(&[("ipv6_probability", "0.5"), ("port_probability", "1")]),
+    )
+    .unwrap();
+    for pattern in patterns(&generator) {
+        assert!(pattern.parse::<SocketAddr>().is_ok(), "{}", pattern);
+    }
+    let generator = IpAddressPatternGenerator::new(
+        "ip",
+        config(&[("ipv6_probability", "0"), ("prefix_probability", "1")]),
+    )
+    .unwrap();
+    for pattern in patterns(&generator) {
+        let (address, prefix) = pattern.split_once('/').unwrap();
+        assert!(address.parse::<Ipv4Addr>().is_ok(), "{}", pattern);
+        assert!(
+            (8..=32).contains(&prefix.parse::<u32>().unwrap()),
+            "{}",
+            pattern
+        );
+    }
+}
+
+#[test]
+fn versions_are_semver() {
+    let generator = SemverPatternGenerator::new(
+        "semver",
+        config(&[
+            ("pre_release_probability", "0.5"),
+            ("build_probability", "0.5"),
+            ("requirement_probability", "0.5"),
+        ]),
+    )
+    .unwrap();
+    // The grammar of semver.org, without leading zeros, behind an optional operator.
+    assert_all_match(
+        &generator,
+        r"^(\^|~|>=|<|=)?(0|[1-9]\d*)\.(0|[1-9]\d*)\.(0|[1-9]\d*)(-[0-9A-Za-z-]+(\.[0-9A-Za-z-]+)*)?(\+[0-9A-Za-z-]+(\.[0-9A-Za-z-]+)*)?$",
+    );
+}
+
+#[test]
+fn dates_and_times_are_iso_8601() {
+    let generator = DateTimePatternGenerator::new(
+        "datetime",
+        config(&[
+            ("fraction_probability", "0.5"),
+            ("timezone_probability", "0.5"),
+        ]),
+    )
+    .unwrap();
+    assert_eq!(generator.kind, DateTimeKind::DateTime);
+    let regex = Regex::new(
+        r"^(\d{4})-(\d{2})-(\d{2})T([01]\d|2[0-3]):[0-5]\d:[0-5]\d(\.\d{3})?(Z|[+-]\d{2}:(00|30|45))?$",
+    )
+    .unwrap();
+    for pattern in patterns(&generator) {
+        let captures = regex
+            .captures(&pattern)
+            .unwrap_or_else(|| panic!("{}", pattern));
+        let year: u32 = captures[1].parse().unwrap();
+        let month: u32 = captures[2].parse().unwrap();
+        let day: u32 = captures[3].parse().unwrap();
+        assert!((1970..=2038).contains(&year), "{}", pattern);
+        assert!((1..=12).contains(&month), "{}", pattern);
+        assert!((1..=31).contains(&day), "{}", pattern);
+    }
+    let generator = DateTimePatternGenerator::new("date", config(&[("kind", "date")])).unwrap();
+    assert_all_match(&generator, r"^\d{4}-\d{2}-\d{2}$");
+    assert!(matches!(
+        DateTimePatternGenerator::new("date", config(&[("kind", "week")])),
+        Err(GeneratorError::InvalidOption { .. })
+    ));
+}
+
+#[test]
+fn colors_durations_and_ports() {
+    let generator = HexColorPatternGenerator::new(
+        "color",
+        config(&[("short_probability", "0.5"), ("alpha_probability", "0.5")]),
+    )
+    .unwrap();
+    assert_all_match(
+        &generator,
+        "^#([0-9a-f]{3}|[0-9a-f]{4}|[0-9a-f]{6}|[0-9a-f]{8})$",
+    );
+
+    let generator = DurationPatternGenerator::new(
+        "duration",
+        config(&[("units", "ms,s,m"), ("compound_probability", "0.5")]),
+    )
+    .unwrap();
+    assert_all_match(&generator, r"^\d+(ms|s|m)(\d+(ms|s))?$");
+
+    let generator = PortPatternGenerator::new(
+        "port",
+        config(&[
+            ("well_known_probability", "0.5"),
+            ("host_probability", "0.5"),
+        ]),
+    )
+    .unwrap();
+    for pattern in patterns(&generator) {
+        let port = pattern.rsplit(':').next().unwrap();
+        assert!(
+            port.parse::<u16>().is_ok_and(|port| port > 0),
+            "{}",
+            pattern
+        );
+    }
+}
+
+#[test]
+fn invalid_fields_fail_the_check() {
+    let mut generator = SemverPatternGenerator::new("semver", HashMap::new()).unwrap();
+    assert!(generator.check().is_ok());
+    generator.build_probability = 1.5;
+    assert!(matches!(
+        generator.check(),
+        Err(GeneratorError::InvalidOption { option, .. }) if option == "build_probability"
+    ));
+
+    let mut generator = IpAddressPatternGenerator::new("ip", HashMap::new()).unwrap();
+    generator.port_probability = -0.1;
+    assert!(generator.check().is_err());
+
+    let mut generator = DurationPatternGenerator::new("duration", HashMap::new()).unwrap();
+    generator.units.clear();
+    assert!(matches!(
+        generator.check(),
+        Err(GeneratorError::NoChoices { .. })
+    ));
+
+    let mut generator = DateTimePatternGenerator::new("datetime", HashMap::new()).unwrap();
+    generator.min_year = 2040;
+    assert!(matches!(
+        generator.check(),
+        Err(GeneratorError::EmptyRange { .. })
+    ));
+}
@@ -0,0 +1,179 @@
+use moonlander_trainer::generators::coding::{create_tree_graph, NumberPatternGenerator};
+use moonlander_trainer::generators::random;
+use moonlander_trainer::generators::randomized::OneOfStringsPatternGenerator;
+use moonlander_trainer::generators::template::{Case, TemplateGenerator};
+use moonlander_trainer::{GeneratorError, GeneratorGraph, TypingPatternGenerator};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+fn template(template: &str) -> Result<TemplateGenerator, GeneratorError> {
+    let symbols: Arc<dyn TypingPatternGenerator> =
+        Arc::new(OneOfStringsPatternGenerator::new("symbols", vec!["fooBar", "baz"]).unwrap());
+    let number: Arc<dyn TypingPatternGenerator> = Arc::new(
+        NumberPatternGenerator::new(
+            "number",
+            HashMap::from([
+                ("min_length", String::from("1")),
+                ("max_length", String::from("3")),
+            ]),
+        )
+        .unwrap(),
+    );
+    TemplateGenerator::new(
+        "template",
+        vec![symbols, number],
+        HashMap::from([("template", template.to_string())]),
+    )
+}
+
+#[test]
+fn placeholders_are_filled_by_named_generators() {
+    let generator = template("{symbols}[{number}].{symbols}()").unwrap();
+    for seed in 0..20 {
+        random::seed(seed);
+        let pattern = generator.generate();
+        let (symbol, rest) = pattern.pattern.split_once('[').unwrap();
+        assert!(["fooBar", "baz"].contains(&symbol), "{}", pattern.pattern);
+        assert!(rest.ends_with("()"), "{}", pattern.pattern);
+        let names: Vec<&str> = pattern.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["symbols", "number", "symbols"]);
+
+        random::seed(seed);
+        let mut into = String::new();
+        generator.generate_into(&mut into);
+        assert_eq!(into, pattern.pattern);
+    }
+}
+
+#[test]
+fn placeholders_repeat_and_change_case() {
+    let generator = template("{{{symbols*2..=3|snake}}} {number*2|upper}").unwrap();
+    for seed in 0..20 {
+        random::seed(seed);
+        let pattern = generator.generate().pattern;
+        let (identifier, numbers) = pattern
+            .strip_prefix('{')
+            .and_then(|p| p.split_once("} "))
+            .unwrap();
+        let words = identifier.split('_').filter(|w| *w != "foo").count();
+        assert!((2..=3).contains(&words), "{}", pattern);
+        assert_eq!(identifier, identifier.to_lowercase());
+        assert_eq!(numbers.split(' ').count(), 2, "{}", pattern);
+    }
+}
+
+#[test]
+fn cases_split_words() {
+    for (case, expected) in [
+        (Case::Snake, "http_server_v2_name"),
+        (Case::ScreamingSnake, "HTTP_SERVER_V2_NAME"),
+        (Case::Kebab, "http-server-v2-name"),
+        (Case::Camel, "httpServerV2Name"),
+        (Case::Pascal, "HttpServerV2Name"),
+    ] {
+        assert_eq!(case.apply("HTTPServer v2_name"), expected);
+    }
+}
+
+#[test]
+fn invalid_templates_are_rejected() {
+    for source in [
+        "{missing}",
+        "{symbols",
+        "symbols}",
+        "{symbols*3..3}",
+        "{symbols*x}",
+        "{number:3..1}",
+        "{symbols:2}",
+        "{symbols|shout}",
+    ] {
+        match template(source) {
+            Err(GeneratorError::InvalidOption { option, .. }) => assert_eq!(option, "template"),
+            other => panic!("{:?} gave {:?}", source, other.map(|g| g.template)),
+        }
+    }
+}
+
+#[test]
+fn templates_are_part_of_snapshots() {
+    let generator = template("{number}: {symbols|pascal}").unwrap();
+    // Only the generators the template uses are kept.
+    assert_eq!(generator.generators.len(), 2);
+    assert_eq!(generator.generators[0].name(), "number");
+    let graph = GeneratorGraph::new(Arc::new(generator), Vec::new());
+    let loaded = GeneratorGraph::from_snapshot(&graph.snapshot().unwrap()).unwrap();
+    random::seed(1);
+    let original = graph.root.generate().pattern;
+    random::seed(1);
+    assert_eq!(loaded.root.generate().pattern, original);
+}
+
+#[test]
+fn cardinality_counts_every_repeat_count() {
+    let generator = template("{symbols*1..=2}!").unwrap();
+    let graph = GeneratorGraph::new(Arc::new(generator), Vec::new());
+    // One symbol or two of them.
+    assert_eq!(graph.cardinality(), Some(2 + 2 * 2));
+}
+
+#[test]
+fn names_have_to_be_unambiguous() {
+    let symbols = || -> Arc<dyn TypingPatternGenerator> {
+        Arc::new(OneOfStringsPatternGenerator::new("symbols", vec!["foo"]).unwrap())
+    };
+    let first = symbols();
+    let config = || HashMap::from([("template", String::from("{symbols}"))]);
+    // Listing the same generator twice is fine, two generators of the same name are not.
+    assert!(
+        TemplateGenerator::new("template", vec![first.clone(), first.clone()], config()).is_ok()
+    );
+    match TemplateGenerator::new("template", vec![first, symbols()], config()) {
+        Err(GeneratorError::InvalidOption { reason, .. }) => {
+            assert!(reason.contains("more than one"), "{}", reason)
+        }
+        other => panic!("gave {:?}", other.map(|g| g.template)),
+    }
+}
+
+#[test]
+fn the_example_works_with_the_default_graph() {
+    let graph = create_tree_graph().unwrap();
+    let mut generators = graph.nodes();
+    generators.extend(graph.named.iter().cloned());
+    let generator = TemplateGenerator::new(
+        "template",
+        generators,
+        HashMap::from([(
+            "template",
+            String::from("{symbols}[{number}].{symbols}({arguments})"),
+        )]),
+    )
+    .unwrap();
+    assert!(generator.generate().pattern.contains("]."));
+}
+
+#[test]
+fn ranges_are_passed_to_the_generator() {
+    let generator = template("{number:5..=6} {number:2..4*2}").unwrap();
+    for seed in 0..50 {
+        random::seed(seed);
+        let pattern = generator.generate().pattern;
+        let numbers: Vec<usize> = pattern.split(' ').map(str::len).collect();
+        assert!((5..=6).contains(&numbers[0]), "{}", pattern);
+        assert!(
+            numbers[1..].iter().all(|n| (2..=3).contains(n)),
+            "{}",
+            pattern
+        );
+        assert_eq!(numbers.len(), 3, "{}", pattern);
+    }
+    // The range changes the copy used by the placeholder, not the generator itself.
+    assert_eq!(generator.generators.len(), 1);
+    let graph = GeneratorGraph::new(Arc::new(generator), Vec::new());
+    assert!(graph.check().is_empty());
+    let loaded = GeneratorGraph::from_snapshot(&graph.snapshot().unwrap()).unwrap();
+    random::seed(3);
+    let original = graph.root.generate().pattern;
+    random::seed(3);
+    assert_eq!(loaded.root.generate().pattern, original);
+}